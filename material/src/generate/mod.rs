@@ -1,6 +1,8 @@
 use crate::generate::result::{CertificatePathGeneratorError, CertificatePathGeneratorResult};
-use der::asn1::Ia5String;
-use der::oid::db::rfc5280::ID_AD_CA_ISSUERS;
+use der::asn1::{BitString, GeneralizedTime, Ia5String, OctetString, Uint, UtcTime};
+use der::oid::db::rfc5280::{ID_AD_CA_ISSUERS, ID_AD_OCSP};
+use der::oid::db::rfc5912::ECDSA_WITH_SHA_256;
+use der::oid::{AssociatedOid, ObjectIdentifier};
 use der::{Decode, Encode};
 use openssl::asn1::{Asn1Integer, Asn1Object, Asn1OctetString, Asn1Time};
 use openssl::bn::{BigNum, MsbOption};
@@ -9,16 +11,36 @@ use openssl::error::ErrorStack;
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 use openssl::pkey::{PKey, PKeyRef, Private};
+use openssl::sign::Signer;
 use openssl::x509::extension::{
     AuthorityKeyIdentifier, BasicConstraints, KeyUsage, SubjectKeyIdentifier,
 };
 use openssl::x509::{X509Builder, X509Extension, X509NameBuilder, X509Ref, X509};
-use x509_cert::ext::pkix::name::GeneralName;
-use x509_cert::ext::pkix::{AccessDescription, AuthorityInfoAccessSyntax};
-use x509_cert::Certificate;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::ext::pkix::certpolicy::PolicyInformation;
+use x509_cert::ext::pkix::crl::dp::DistributionPoint;
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+use x509_cert::ext::pkix::{
+    AccessDescription, AuthorityInfoAccessSyntax, CertificatePolicies, CrlDistributionPoints,
+    CrlNumber, ExtendedKeyUsage, SubjectAltName,
+};
+use x509_cert::ext::Extension;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::time::Time;
+use x509_cert::{Certificate, TbsCertificate, Version};
+use x509_ocsp::{
+    BasicOcspResponse, CertStatus, OcspGeneralizedTime, OcspRequest, OcspResponse, ResponderId,
+    ResponseData, RevokedInfo, SingleResponse, Version as OcspVersion,
+};
 
 pub mod result;
 
+// embedded SignedCertificateTimestampList extension
+const ID_CE_SCT_LIST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.11129.2.4.2");
+
 pub struct CertificatePathGenerator {}
 
 impl CertificatePathGenerator {
@@ -85,8 +107,17 @@ impl CertificatePathGenerator {
 
         builder.set_serial_number(Asn1Integer::from_bn(serial.as_ref())?.as_ref())?;
 
+        let key = Self::gen_keypair()?;
+        builder.set_pubkey(key.as_ref())?;
+
         builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
-        builder.append_extension(KeyUsage::new().critical().key_cert_sign().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()?,
+        )?;
         builder.append_extension(
             SubjectKeyIdentifier::new().build(&builder.x509v3_context(None, None))?,
         )?;
@@ -98,9 +129,6 @@ impl CertificatePathGenerator {
 
         builder.set_subject_name(name.as_ref())?;
 
-        let key = Self::gen_keypair()?;
-        builder.set_pubkey(key.as_ref())?;
-
         builder.sign(key.as_ref(), MessageDigest::sha256())?;
 
         Ok((builder.build(), key))
@@ -124,8 +152,17 @@ impl CertificatePathGenerator {
 
         builder.set_serial_number(Asn1Integer::from_bn(serial.as_ref())?.as_ref())?;
 
+        let key = Self::gen_keypair()?;
+        builder.set_pubkey(key.as_ref())?;
+
         builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
-        builder.append_extension(KeyUsage::new().critical().key_cert_sign().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()?,
+        )?;
         builder.append_extension(
             SubjectKeyIdentifier::new().build(&builder.x509v3_context(Some(issuer), None))?,
         )?;
@@ -149,21 +186,9 @@ impl CertificatePathGenerator {
                 },
             );
 
-        builder.append_extension(X509Extension::new_from_der(
-            Asn1Object::from_str(Nid::INFO_ACCESS.short_name()?.as_ref())?.as_ref(),
-            false,
-            Asn1OctetString::new_from_bytes(
-                AuthorityInfoAccessSyntax(vec![AccessDescription {
-                    access_method: ID_AD_CA_ISSUERS,
-                    access_location: GeneralName::UniformResourceIdentifier(Ia5String::new(
-                        format!("test://{}", issuer_name).as_str(),
-                    )?),
-                }])
-                .to_der()?
-                .as_slice(),
-            )?
-            .as_ref(),
-        )?)?;
+        builder.append_extension(Self::authority_info_access(issuer_name.as_str())?)?;
+
+        builder.append_extension(Self::crl_distribution_points(issuer_name.as_str())?)?;
 
         builder.set_issuer_name(issuer.subject_name())?;
 
@@ -172,9 +197,6 @@ impl CertificatePathGenerator {
         let name = name.build();
         builder.set_subject_name(name.as_ref())?;
 
-        let key = Self::gen_keypair()?;
-        builder.set_pubkey(key.as_ref())?;
-
         builder.sign(issuer_key, MessageDigest::sha256())?;
         Ok((builder.build(), key))
     }
@@ -196,6 +218,9 @@ impl CertificatePathGenerator {
 
         builder.set_serial_number(Asn1Integer::from_bn(serial.as_ref())?.as_ref())?;
 
+        let key = Self::gen_keypair()?;
+        builder.set_pubkey(key.as_ref())?;
+
         builder.append_extension(BasicConstraints::new().critical().build()?)?;
         builder.append_extension(KeyUsage::new().critical().digital_signature().build()?)?;
         builder.append_extension(
@@ -221,21 +246,9 @@ impl CertificatePathGenerator {
                 },
             );
 
-        builder.append_extension(X509Extension::new_from_der(
-            Asn1Object::from_str(Nid::INFO_ACCESS.short_name()?.as_ref())?.as_ref(),
-            false,
-            Asn1OctetString::new_from_bytes(
-                AuthorityInfoAccessSyntax(vec![AccessDescription {
-                    access_method: ID_AD_CA_ISSUERS,
-                    access_location: GeneralName::UniformResourceIdentifier(Ia5String::new(
-                        format!("test://{}", issuer_name).as_str(),
-                    )?),
-                }])
-                .to_der()?
-                .as_slice(),
-            )?
-            .as_ref(),
-        )?)?;
+        builder.append_extension(Self::authority_info_access(issuer_name.as_str())?)?;
+
+        builder.append_extension(Self::crl_distribution_points(issuer_name.as_str())?)?;
 
         builder.set_issuer_name(issuer.subject_name())?;
 
@@ -244,9 +257,6 @@ impl CertificatePathGenerator {
         let name = name.build();
         builder.set_subject_name(name.as_ref())?;
 
-        let key = Self::gen_keypair()?;
-        builder.set_pubkey(key.as_ref())?;
-
         builder.sign(issuer_key, MessageDigest::sha256())?;
         Ok((builder.build(), key))
     }
@@ -271,8 +281,16 @@ impl CertificatePathGenerator {
 
         builder.set_serial_number(Asn1Integer::from_bn(serial.as_ref())?.as_ref())?;
 
+        builder.set_pubkey(target.public_key()?.as_ref())?;
+
         builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
-        builder.append_extension(KeyUsage::new().critical().key_cert_sign().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()?,
+        )?;
         builder.append_extension(
             SubjectKeyIdentifier::new()
                 .build(&builder.x509v3_context(Some(issuer.as_ref()), None))?,
@@ -286,8 +304,6 @@ impl CertificatePathGenerator {
         builder.set_issuer_name(issuer.subject_name())?;
         builder.set_subject_name(target.subject_name())?;
 
-        builder.set_pubkey(target.public_key()?.as_ref())?;
-
         let issuer_key = PKey::private_key_from_der(issuer_key)?;
 
         builder.sign(issuer_key.as_ref(), MessageDigest::sha256())?;
@@ -296,6 +312,411 @@ impl CertificatePathGenerator {
         )?)
     }
 
+    /// CRL issued by `issuer`, listing `revoked` certificates, valid for `validity`
+    pub fn crl(
+        issuer: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        revoked: &[&x509_cert::Certificate],
+        validity: Duration,
+    ) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CertificatePathGeneratorError::Error(e.to_string()))?;
+        let this_update = Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(
+            now.as_secs(),
+        ))?);
+        let next_update = Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(
+            (now + validity).as_secs(),
+        ))?);
+
+        let algorithm = AlgorithmIdentifierOwned {
+            oid: ECDSA_WITH_SHA_256,
+            parameters: None,
+        };
+
+        let tbs_cert_list = TbsCertList {
+            version: Version::V2,
+            signature: algorithm.clone(),
+            issuer: issuer.tbs_certificate.subject.clone(),
+            this_update,
+            next_update: Some(next_update),
+            // must be absent when empty
+            revoked_certificates: (!revoked.is_empty()).then(|| {
+                revoked
+                    .iter()
+                    .map(|c| RevokedCert {
+                        serial_number: c.tbs_certificate.serial_number.clone(),
+                        revocation_date: this_update,
+                        crl_entry_extensions: None,
+                    })
+                    .collect()
+            }),
+            crl_extensions: Some(vec![Extension {
+                extn_id: CrlNumber::OID,
+                critical: false,
+                extn_value: OctetString::new(CrlNumber(Uint::new(&[1])?).to_der()?)?,
+            }]),
+        };
+
+        let issuer_key = PKey::private_key_from_der(issuer_key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), issuer_key.as_ref())?;
+        let signature = signer.sign_oneshot_to_vec(tbs_cert_list.to_der()?.as_slice())?;
+
+        Ok(CertificateList {
+            tbs_cert_list,
+            signature_algorithm: algorithm,
+            signature: BitString::from_bytes(signature.as_slice())?,
+        }
+        .to_der()?)
+    }
+
+    /// OCSP response from `issuer` to the DER encoded `request`, reporting `revoked` certificates as revoked and
+    /// all others as good, valid for `validity`
+    pub fn ocsp(
+        issuer: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        request: &[u8],
+        revoked: &[&x509_cert::Certificate],
+        validity: Duration,
+    ) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let now = SystemTime::now();
+        Self::ocsp_at(
+            issuer,
+            issuer_key,
+            request,
+            revoked,
+            now,
+            Some(now + validity),
+        )
+    }
+
+    /// OCSP response from `issuer` to the DER encoded `request`, reporting `revoked` certificates as revoked and
+    /// all others as good, produced at `this_update` with an optional `next_update`
+    pub fn ocsp_at(
+        issuer: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        request: &[u8],
+        revoked: &[&x509_cert::Certificate],
+        this_update: SystemTime,
+        next_update: Option<SystemTime>,
+    ) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let time = |t: SystemTime| -> CertificatePathGeneratorResult<OcspGeneralizedTime> {
+            let t = t
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| CertificatePathGeneratorError::Error(e.to_string()))?;
+            Ok(OcspGeneralizedTime(GeneralizedTime::from_unix_duration(
+                Duration::from_secs(t.as_secs()),
+            )?))
+        };
+        let this_update = time(this_update)?;
+        let next_update = next_update.map(time).transpose()?;
+
+        let request = OcspRequest::from_der(request)?;
+        let responses = request
+            .tbs_request
+            .request_list
+            .into_iter()
+            .map(|r| {
+                let cert_status = if revoked
+                    .iter()
+                    .any(|c| c.tbs_certificate.serial_number == r.req_cert.serial_number)
+                {
+                    CertStatus::revoked(RevokedInfo {
+                        revocation_time: this_update,
+                        revocation_reason: Some(CrlReason::KeyCompromise),
+                    })
+                } else {
+                    CertStatus::good()
+                };
+                SingleResponse {
+                    cert_id: r.req_cert,
+                    cert_status,
+                    this_update,
+                    next_update,
+                    single_extensions: None,
+                }
+            })
+            .collect();
+
+        let tbs_response_data = ResponseData {
+            version: OcspVersion::V1,
+            responder_id: ResponderId::ByName(issuer.tbs_certificate.subject.clone()),
+            produced_at: this_update,
+            responses,
+            response_extensions: None,
+        };
+
+        let issuer_key = PKey::private_key_from_der(issuer_key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), issuer_key.as_ref())?;
+        let signature = signer.sign_oneshot_to_vec(tbs_response_data.to_der()?.as_slice())?;
+
+        Ok(OcspResponse::successful(BasicOcspResponse {
+            tbs_response_data,
+            signature_algorithm: AlgorithmIdentifierOwned {
+                oid: ECDSA_WITH_SHA_256,
+                parameters: None,
+            },
+            signature: BitString::from_bytes(signature.as_slice())?,
+            certs: None,
+        })?
+        .to_der()?)
+    }
+
+    /// Key pair for a test CT log, as DER encoded private key and public key
+    pub fn ct_log_key() -> CertificatePathGeneratorResult<(Vec<u8>, Vec<u8>)> {
+        let key = Self::gen_keypair()?;
+        Ok((key.private_key_to_der()?, key.public_key_to_der()?))
+    }
+
+    /// SCT from the log with `log_key`, over the precertificate of `certificate` issued by `issuer`
+    pub fn precert_sct(
+        certificate: &x509_cert::Certificate,
+        issuer: &x509_cert::Certificate,
+        log_key: &[u8],
+    ) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let mut entry = vec![0, 1];
+        entry.extend(Sha256::digest(
+            issuer.tbs_certificate.subject_public_key_info.to_der()?,
+        ));
+        let tbs = certificate.tbs_certificate.to_der()?;
+        entry.extend(&(tbs.len() as u32).to_be_bytes()[1..]);
+        entry.extend(tbs);
+        Self::sct(entry, log_key)
+    }
+
+    /// SCT from the log with `log_key`, over `certificate` itself
+    pub fn x509_sct(
+        certificate: &x509_cert::Certificate,
+        log_key: &[u8],
+    ) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let mut entry = vec![0, 0];
+        let der = certificate.to_der()?;
+        entry.extend(&(der.len() as u32).to_be_bytes()[1..]);
+        entry.extend(der);
+        Self::sct(entry, log_key)
+    }
+
+    /// TLS encoded `SignedCertificateTimestampList` of `scts`
+    pub fn sct_list(scts: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = vec![];
+        for sct in scts {
+            body.extend((sct.len() as u16).to_be_bytes());
+            body.extend(sct);
+        }
+        let mut list = (body.len() as u16).to_be_bytes().to_vec();
+        list.extend(body);
+        list
+    }
+
+    /// `certificate` with `scts` embedded, signed by `issuer_key`
+    pub fn embed_scts(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        scts: &[Vec<u8>],
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        Self::with_extension(
+            certificate,
+            issuer_key,
+            Extension {
+                extn_id: ID_CE_SCT_LIST,
+                critical: false,
+                extn_value: OctetString::new(OctetString::new(Self::sct_list(scts))?.to_der()?)?,
+            },
+        )
+    }
+
+    /// `certificate` with subject alternative `names`, signed by `issuer_key`
+    pub fn with_subject_alt_names(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        names: Vec<GeneralName>,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        Self::with_extension(
+            certificate,
+            issuer_key,
+            Extension {
+                extn_id: SubjectAltName::OID,
+                critical: false,
+                extn_value: OctetString::new(SubjectAltName(names).to_der()?)?,
+            },
+        )
+    }
+
+    /// `certificate` with extended key `usages`, signed by `issuer_key`
+    pub fn with_extended_key_usage(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        usages: Vec<ObjectIdentifier>,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        Self::with_extension(
+            certificate,
+            issuer_key,
+            Extension {
+                extn_id: ExtendedKeyUsage::OID,
+                critical: false,
+                extn_value: OctetString::new(ExtendedKeyUsage(usages).to_der()?)?,
+            },
+        )
+    }
+
+    /// `certificate` asserting certificate `policies`, signed by `issuer_key`
+    pub fn with_policies(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        policies: Vec<ObjectIdentifier>,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        let policies = policies
+            .into_iter()
+            .map(|policy_identifier| PolicyInformation {
+                policy_identifier,
+                policy_qualifiers: None,
+            })
+            .collect();
+        Self::with_extension(
+            certificate,
+            issuer_key,
+            Extension {
+                extn_id: CertificatePolicies::OID,
+                critical: false,
+                extn_value: OctetString::new(CertificatePolicies(policies).to_der()?)?,
+            },
+        )
+    }
+
+    /// `certificate` with `extension` appended, signed by `issuer_key`
+    pub fn with_extension(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        extension: Extension,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        let mut tbs_certificate = certificate.tbs_certificate.clone();
+        tbs_certificate
+            .extensions
+            .get_or_insert_with(Vec::new)
+            .push(extension);
+        Self::sign(certificate, tbs_certificate, issuer_key)
+    }
+
+    /// `certificate` without extensions identified by `oid`, signed by `issuer_key`
+    pub fn without_extension(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        oid: ObjectIdentifier,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        let mut tbs_certificate = certificate.tbs_certificate.clone();
+        if let Some(extensions) = tbs_certificate.extensions.as_mut() {
+            extensions.retain(|e| e.extn_id != oid);
+        }
+        Self::sign(certificate, tbs_certificate, issuer_key)
+    }
+
+    /// `certificate` expiring after `validity` from now, signed by `issuer_key`
+    pub fn with_validity(
+        certificate: &x509_cert::Certificate,
+        issuer_key: &[u8],
+        validity: Duration,
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CertificatePathGeneratorError::Error(e.to_string()))?;
+        let mut tbs_certificate = certificate.tbs_certificate.clone();
+        tbs_certificate.validity.not_after = Time::UtcTime(UtcTime::from_unix_duration(
+            Duration::from_secs((now + validity).as_secs()),
+        )?);
+        Self::sign(certificate, tbs_certificate, issuer_key)
+    }
+
+    // `certificate` with `tbs_certificate` signed by `issuer_key`
+    fn sign(
+        certificate: &x509_cert::Certificate,
+        tbs_certificate: TbsCertificate,
+        issuer_key: &[u8],
+    ) -> CertificatePathGeneratorResult<x509_cert::Certificate> {
+        let issuer_key = PKey::private_key_from_der(issuer_key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), issuer_key.as_ref())?;
+        let signature = signer.sign_oneshot_to_vec(tbs_certificate.to_der()?.as_slice())?;
+
+        Ok(x509_cert::Certificate {
+            tbs_certificate,
+            signature_algorithm: certificate.signature_algorithm.clone(),
+            signature: BitString::from_bytes(signature.as_slice())?,
+        })
+    }
+
+    // v1 sct over signed `entry`, with ecdsa sha-256 signature
+    fn sct(entry: Vec<u8>, log_key: &[u8]) -> CertificatePathGeneratorResult<Vec<u8>> {
+        let log_key = PKey::private_key_from_der(log_key)?;
+        let log_id = Sha256::digest(log_key.public_key_to_der()?);
+        // a second ago, so not in the future for verifiers
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CertificatePathGeneratorError::Error(e.to_string()))?
+            .as_millis() as u64
+            - 1000;
+
+        let mut signed = vec![0, 0];
+        signed.extend(timestamp.to_be_bytes());
+        signed.extend(entry);
+        signed.extend([0, 0]);
+        let mut signer = Signer::new(MessageDigest::sha256(), log_key.as_ref())?;
+        let signature = signer.sign_oneshot_to_vec(signed.as_slice())?;
+
+        let mut sct = vec![0];
+        sct.extend(log_id);
+        sct.extend(timestamp.to_be_bytes());
+        sct.extend([0, 0, 4, 3]);
+        sct.extend((signature.len() as u16).to_be_bytes());
+        sct.extend(signature);
+        Ok(sct)
+    }
+
+    fn authority_info_access(issuer_name: &str) -> CertificatePathGeneratorResult<X509Extension> {
+        Ok(X509Extension::new_from_der(
+            Asn1Object::from_str(Nid::INFO_ACCESS.short_name()?.as_ref())?.as_ref(),
+            false,
+            Asn1OctetString::new_from_bytes(
+                AuthorityInfoAccessSyntax(vec![
+                    AccessDescription {
+                        access_method: ID_AD_CA_ISSUERS,
+                        access_location: GeneralName::UniformResourceIdentifier(Ia5String::new(
+                            format!("test://{}", issuer_name).as_str(),
+                        )?),
+                    },
+                    AccessDescription {
+                        access_method: ID_AD_OCSP,
+                        access_location: GeneralName::UniformResourceIdentifier(Ia5String::new(
+                            format!("test://{}/ocsp", issuer_name).as_str(),
+                        )?),
+                    },
+                ])
+                .to_der()?
+                .as_slice(),
+            )?
+            .as_ref(),
+        )?)
+    }
+
+    fn crl_distribution_points(issuer_name: &str) -> CertificatePathGeneratorResult<X509Extension> {
+        Ok(X509Extension::new_from_der(
+            Asn1Object::from_str(Nid::CRL_DISTRIBUTION_POINTS.short_name()?.as_ref())?.as_ref(),
+            false,
+            Asn1OctetString::new_from_bytes(
+                CrlDistributionPoints(vec![DistributionPoint {
+                    distribution_point: Some(DistributionPointName::FullName(vec![
+                        GeneralName::UniformResourceIdentifier(Ia5String::new(
+                            format!("test://{}/crl", issuer_name).as_str(),
+                        )?),
+                    ])),
+                    reasons: None,
+                    crl_issuer: None,
+                }])
+                .to_der()?
+                .as_slice(),
+            )?
+            .as_ref(),
+        )?)
+    }
+
     fn gen_keypair() -> Result<PKey<Private>, ErrorStack> {
         let nid = Nid::X9_62_PRIME256V1;
         let group = EcGroup::from_curve_name(nid)?;
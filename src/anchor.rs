@@ -0,0 +1,131 @@
+//! Trust anchors
+
+use crate::certificate::Certificate;
+use der::{Decode, Encode};
+#[cfg(feature = "rustls-webpki")]
+use sha1::{Digest, Sha1};
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::name::Name;
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+
+/// Trust anchor, identified by subject and [SPKI](https://datatracker.ietf.org/doc/html/rfc5280#section-4.1.2.7).
+/// When the top certificate of a path is issued by a trust anchor, [`X509PathFinder`](crate::X509PathFinder)
+/// presents the path for validation before searching any further.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TrustAnchor {
+    subject: String,
+    name: Vec<u8>,
+    spki: Vec<u8>,
+    key_identifiers: Vec<Vec<u8>>,
+}
+
+impl TrustAnchor {
+    /// Instantiate from `subject` and `spki`
+    pub fn new(subject: &Name, spki: &SubjectPublicKeyInfoOwned) -> der::Result<Self> {
+        Ok(Self {
+            subject: subject.to_string(),
+            name: subject.to_der()?,
+            spki: spki.to_der()?,
+            key_identifiers: key_identifiers(spki),
+        })
+    }
+
+    /// Anchor subject
+    pub fn subject(&self) -> &str {
+        self.subject.as_str()
+    }
+
+    /// DER-encoded anchor subject
+    pub fn subject_der(&self) -> &[u8] {
+        self.name.as_slice()
+    }
+
+    /// DER-encoded anchor SPKI
+    pub fn spki(&self) -> &[u8] {
+        self.spki.as_slice()
+    }
+
+    // anchor issued certificate, matching by subject and key identifier, or by signature without a key identifier
+    pub(crate) fn issued(&self, certificate: &Certificate) -> bool {
+        self.matches(
+            certificate.issuer(),
+            certificate.authority_key_identifier(),
+            certificate.inner(),
+        )
+    }
+
+    // as `issued`, for a certificate not yet parsed for path finding
+    pub(crate) fn issued_x509(&self, certificate: &crate::Certificate) -> bool {
+        self.matches(
+            certificate.tbs_certificate.issuer.to_string().as_str(),
+            Certificate::parse_authority_key_identifier(certificate).as_deref(),
+            certificate,
+        )
+    }
+
+    fn matches(
+        &self,
+        issuer: &str,
+        key_identifier: Option<&[u8]>,
+        certificate: &crate::Certificate,
+    ) -> bool {
+        if self.subject != issuer {
+            return false;
+        }
+
+        match key_identifier {
+            None => self.signed(certificate),
+            Some(key_identifier) => self
+                .key_identifiers
+                .iter()
+                .any(|k| k.as_slice() == key_identifier),
+        }
+    }
+
+    // anchor key signed certificate
+    #[cfg(feature = "rustls-webpki")]
+    fn signed(&self, certificate: &crate::Certificate) -> bool {
+        SubjectPublicKeyInfoOwned::from_der(self.spki.as_slice()).is_ok_and(|spki| {
+            crate::signature::verify_certificate(crate::signature::ALGORITHMS, &spki, certificate)
+                .is_ok()
+        })
+    }
+
+    // signatures aren't verified without webpki, so key identifiers are required
+    #[cfg(not(feature = "rustls-webpki"))]
+    fn signed(&self, _certificate: &crate::Certificate) -> bool {
+        false
+    }
+}
+
+// RFC 5280 4.2.1.2, method (1)
+#[cfg(feature = "rustls-webpki")]
+fn key_identifiers(spki: &SubjectPublicKeyInfoOwned) -> Vec<Vec<u8>> {
+    vec![Sha1::digest(spki.subject_public_key.raw_bytes()).to_vec()]
+}
+
+// SHA-1 isn't available without webpki, so anchors are only identified by a subject key identifier extension
+#[cfg(not(feature = "rustls-webpki"))]
+fn key_identifiers(_spki: &SubjectPublicKeyInfoOwned) -> Vec<Vec<u8>> {
+    vec![]
+}
+
+impl TryFrom<&crate::Certificate> for TrustAnchor {
+    type Error = der::Error;
+
+    fn try_from(certificate: &crate::Certificate) -> Result<Self, Self::Error> {
+        let mut anchor = Self::new(
+            &certificate.tbs_certificate.subject,
+            &certificate.tbs_certificate.subject_public_key_info,
+        )?;
+
+        if let Some((_, ski)) = certificate.tbs_certificate.get::<SubjectKeyIdentifier>()? {
+            let ski = ski.0.into_bytes();
+            if !anchor.key_identifiers.contains(&ski) {
+                anchor.key_identifiers.push(ski);
+            }
+        }
+
+        Ok(anchor)
+    }
+}
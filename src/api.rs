@@ -1,6 +1,13 @@
 //! API Traits
 
+use crate::report::{CacheCounters, CertificateOrigin};
+use async_trait::async_trait;
 use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::SystemTime;
+use url::Url;
 
 /// Certificate path validation. Implement to customize behavior. Note: X509 certificate [path validation](https://datatracker.ietf.org/doc/html/rfc5280#section-6) is not
 /// trivial. Implement to add business logic, but leverage a trusted X509 validator within.
@@ -13,9 +20,160 @@ pub trait PathValidator {
         &self,
         path: Vec<&crate::Certificate>,
     ) -> Result<CertificatePathValidation, Self::PathValidatorError>;
+
+    /// Validates `path` with out-of-band `evidence`, returning the revocation evidence that proved the path not revoked
+    /// along with [`Found`](crate::api::CertificatePathValidation::Found). Revocation checking validators consult
+    /// `evidence` before fetching any. Ignores evidence by default.
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let _ = evidence;
+        Ok((self.validate(path)?, vec![]))
+    }
+
+    /// Checks `issuer` as a candidate issuer of `child`, before it is appended to a path.
+    /// Returning [`NotFound`](crate::api::CertificatePathValidation::NotFound) vetoes the link, pruning every path through it.
+    /// Accepts every link by default.
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        let _ = (child, issuer);
+        Ok(CertificatePathValidation::Found)
+    }
+
+    /// Cumulative validation cache counters, reported in [`Report::cache`](crate::report::Report::cache).
+    /// Zero for validators without a cache.
+    fn cache_counters(&self) -> CacheCounters {
+        CacheCounters::default()
+    }
 }
 
-/// Result of [`validate`](crate::api::PathValidator::validate)
+/// Asynchronous certificate path validation. Implement with [`async_trait`](https://docs.rs/async-trait) when validation
+/// requires I/O, such as OCSP or CRL lookups. Every [`PathValidator`] whose error is `Send` is
+/// also an `AsyncPathValidator`, whether or not the validator is `Sync`.
+#[async_trait]
+pub trait AsyncPathValidator {
+    /// Error type
+    type PathValidatorError: PathValidatorError;
+
+    /// Validates `path`, returning results as [`CertificatePathValidation`]
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError>;
+
+    // default methods are hand-written, as `#[async_trait]` would bound them with `Self: Sync`
+
+    /// Validates `path` with out-of-band `evidence`. See [`PathValidator::validate_with_evidence`].
+    fn validate_with_evidence<'validator, 'path, 'evidence, 'future>(
+        &'validator self,
+        path: Vec<&'path crate::Certificate>,
+        evidence: &'evidence Evidence,
+    ) -> BoxFuture<'future, WithEvidence<Self::PathValidatorError>>
+    where
+        'validator: 'future,
+        'path: 'future,
+        'evidence: 'future,
+        Self: 'future,
+    {
+        let _ = evidence;
+        // future holds the validation future rather than `self`, so `Self` needn't be `Sync`
+        let validation = self.validate(path);
+        Box::pin(async move { Ok((validation.await?, vec![])) })
+    }
+
+    /// Checks `issuer` as a candidate issuer of `child`. See [`PathValidator::check_link`].
+    fn check_link<'validator, 'child, 'issuer, 'future>(
+        &'validator self,
+        child: &'child crate::Certificate,
+        issuer: &'issuer crate::Certificate,
+    ) -> BoxFuture<'future, Result<CertificatePathValidation, Self::PathValidatorError>>
+    where
+        'validator: 'future,
+        'child: 'future,
+        'issuer: 'future,
+        Self: 'future,
+    {
+        let _ = (child, issuer);
+        Box::pin(async { Ok(CertificatePathValidation::Found) })
+    }
+
+    /// Cumulative validation cache counters. See [`PathValidator::cache_counters`].
+    fn cache_counters(&self) -> CacheCounters {
+        CacheCounters::default()
+    }
+}
+
+// validation, and the revocation evidence that proved a path not revoked
+type WithEvidence<E> = Result<(CertificatePathValidation, Vec<RevocationEvidence>), E>;
+
+/// Boxed `Send` future, as returned by [`AsyncPathValidator`] methods
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// hand-written rather than `#[async_trait]`, which would require `V: Sync`. Sync validation completes
+// before the future is returned, so the future doesn't borrow the validator
+impl<V> AsyncPathValidator for V
+where
+    V: PathValidator,
+    V::PathValidatorError: Send,
+{
+    type PathValidatorError = <V as PathValidator>::PathValidatorError;
+
+    fn validate<'validator, 'path, 'future>(
+        &'validator self,
+        path: Vec<&'path crate::Certificate>,
+    ) -> BoxFuture<'future, Result<CertificatePathValidation, Self::PathValidatorError>>
+    where
+        'validator: 'future,
+        'path: 'future,
+        Self: 'future,
+    {
+        Box::pin(std::future::ready(PathValidator::validate(self, path)))
+    }
+
+    fn validate_with_evidence<'validator, 'path, 'evidence, 'future>(
+        &'validator self,
+        path: Vec<&'path crate::Certificate>,
+        evidence: &'evidence Evidence,
+    ) -> BoxFuture<'future, WithEvidence<Self::PathValidatorError>>
+    where
+        'validator: 'future,
+        'path: 'future,
+        'evidence: 'future,
+        Self: 'future,
+    {
+        Box::pin(std::future::ready(PathValidator::validate_with_evidence(
+            self, path, evidence,
+        )))
+    }
+
+    fn check_link<'validator, 'child, 'issuer, 'future>(
+        &'validator self,
+        child: &'child crate::Certificate,
+        issuer: &'issuer crate::Certificate,
+    ) -> BoxFuture<'future, Result<CertificatePathValidation, Self::PathValidatorError>>
+    where
+        'validator: 'future,
+        'child: 'future,
+        'issuer: 'future,
+        Self: 'future,
+    {
+        Box::pin(std::future::ready(PathValidator::check_link(
+            self, child, issuer,
+        )))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        PathValidator::cache_counters(self)
+    }
+}
+
+/// Result of [`validate`](crate::api::PathValidator::validate) and [`check_link`](crate::api::PathValidator::check_link)
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum CertificatePathValidation {
     /// Valid path found
@@ -24,5 +182,108 @@ pub enum CertificatePathValidation {
     NotFound(String),
 }
 
+/// Out-of-band evidence, supplied to [`find_with_evidence`](crate::X509PathFinder::find_with_evidence)
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Evidence {
+    /// Revocation evidence, such as a stapled OCSP response
+    pub revocation: Vec<RevocationEvidence>,
+    /// TLS encoded `SignedCertificateTimestampList`s for the target, such as one from the TLS
+    /// `signed_certificate_timestamp` extension. Used by [`CtPathValidator`](crate::provided::validator::ct::CtPathValidator).
+    pub scts: Vec<Vec<u8>>,
+    /// Time to validate at, such as the time of a TLS handshake. Used by
+    /// [`DefaultPathValidator`](crate::provided::validator::default::DefaultPathValidator) in place of the current time.
+    pub time: Option<SystemTime>,
+}
+
+impl From<Vec<RevocationEvidence>> for Evidence {
+    fn from(revocation: Vec<RevocationEvidence>) -> Self {
+        Self {
+            revocation,
+            scts: vec![],
+            time: None,
+        }
+    }
+}
+
+/// Out-of-band revocation evidence, supplied in [`Evidence`].
+/// Revocation evidence that proved a path not revoked is reported in [`Found::revocation`](crate::report::Found::revocation).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum RevocationEvidence {
+    /// DER encoded OCSP response, such as one stapled in a TLS handshake
+    Ocsp(Vec<u8>),
+    /// DER encoded CRL, such as one shipped in CMS revocation info
+    Crl(Vec<u8>),
+}
+
 /// Error trait
 pub trait PathValidatorError: Display + Debug {}
+
+/// Resource transport, used to fetch revocation data such as CRLs and OCSP responses. Implement to customize
+/// transport, or to serve resources from memory.
+#[async_trait]
+pub trait Fetcher: Sync {
+    /// Error type
+    type FetcherError: FetcherError;
+
+    /// Fetches the resource at `url`, returning its bytes
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, Self::FetcherError>;
+
+    /// Posts `body` of `content_type` to `url`, returning the response bytes. Not supported by default.
+    async fn post(
+        &self,
+        url: &Url,
+        _content_type: &str,
+        _body: Vec<u8>,
+    ) -> Result<Vec<u8>, Self::FetcherError> {
+        Err(Self::FetcherError::unsupported("post", url))
+    }
+}
+
+/// Fetcher error trait
+pub trait FetcherError: Display + Debug {
+    /// Error for an `operation` on `url` the fetcher doesn't support
+    fn unsupported(operation: &str, url: &Url) -> Self
+    where
+        Self: Sized;
+}
+
+/// Certificate store searched for issuer candidates. Implement to back [`X509PathFinder`](crate::X509PathFinder) with
+/// on-disk or remote storage, such as a database shared across services. [`MemoryStore`](crate::store::MemoryStore)
+/// is the default. Implementations can verify their behavior with [`conformance`](crate::store::conformance).
+#[async_trait]
+pub trait CertificateBackend: Send + Sync {
+    /// Error type
+    type CertificateBackendError: CertificateBackendError;
+
+    /// Stored certificates whose subject is the issuer of `certificate`, in priority order, each with the origin it was
+    /// stored with: [`CertificateOrigin::Store`] when inserted, or the AIA URL
+    /// it was downloaded from. The origin is reported in paths through the certificate.
+    async fn issuers(
+        &self,
+        certificate: &crate::Certificate,
+    ) -> Result<Vec<(Arc<crate::Certificate>, CertificateOrigin)>, Self::CertificateBackendError>;
+
+    /// Insert `certificate` with the lowest priority, such as one downloaded from an AIA URL. Returns `false` if the
+    /// certificate is already stored, refused, such as a self-signed certificate, or not kept, such as one evicted to
+    /// stay within bounds. See [`SelfSignedPolicy`](crate::store::SelfSignedPolicy).
+    async fn insert(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+    ) -> Result<bool, Self::CertificateBackendError>;
+
+    /// Insert `certificate` downloaded from AIA `url`, with the lowest priority. Implement to record origin, defaults to
+    /// [`insert`](Self::insert).
+    async fn insert_from_url(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+        url: &Url,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        let _ = url;
+        self.insert(certificate).await
+    }
+}
+
+/// Certificate store error trait
+pub trait CertificateBackendError: Display + Debug {}
+
+impl CertificateBackendError for std::convert::Infallible {}
@@ -1,13 +1,15 @@
 use crate::report::CertificateOrigin;
-use der::oid::db::rfc5280::{ID_AD_CA_ISSUERS, ID_PE_AUTHORITY_INFO_ACCESS};
+use der::oid::db::rfc5280::{ID_AD_CA_ISSUERS, ID_AD_OCSP, ID_PE_AUTHORITY_INFO_ACCESS};
+use der::oid::ObjectIdentifier;
 use der::{Decode, DecodeValue, Encode, Header, Length, Reader, Writer};
 use sha2::{Digest, Sha256};
-use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use url::Url;
-use x509_cert::ext::pkix::name::GeneralName;
-use x509_cert::ext::pkix::AuthorityInfoAccessSyntax;
+use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+use x509_cert::ext::pkix::{
+    AuthorityInfoAccessSyntax, AuthorityKeyIdentifier, CrlDistributionPoints,
+};
 
 #[derive(Clone, Debug)]
 pub struct Certificate {
@@ -15,8 +17,8 @@ pub struct Certificate {
     issuer: String,
     subject: String,
     aia: Vec<Url>,
-    ord: usize,
-    hash: Vec<u8>,
+    authority_key_identifier: Option<Vec<u8>>,
+    fingerprint: Vec<u8>,
     origin: CertificateOrigin,
 }
 
@@ -29,6 +31,18 @@ impl Certificate {
     }
 
     fn parse_aia(certificate: &crate::Certificate) -> Vec<Url> {
+        Self::parse_access_locations(certificate, ID_AD_CA_ISSUERS)
+    }
+
+    // ocsp responder urls
+    pub fn parse_ocsp(certificate: &crate::Certificate) -> Vec<Url> {
+        Self::parse_access_locations(certificate, ID_AD_OCSP)
+    }
+
+    fn parse_access_locations(
+        certificate: &crate::Certificate,
+        access_method: ObjectIdentifier,
+    ) -> Vec<Url> {
         match &certificate.tbs_certificate.extensions {
             None => vec![],
             Some(extensions) => extensions
@@ -43,7 +57,7 @@ impl Certificate {
                 })
                 .flatten()
                 .filter_map(|i| {
-                    if i.access_method == ID_AD_CA_ISSUERS {
+                    if i.access_method == access_method {
                         if let GeneralName::UniformResourceIdentifier(uri) = i.access_location {
                             Url::parse(uri.as_str()).ok()
                         } else {
@@ -57,12 +71,59 @@ impl Certificate {
         }
     }
 
-    pub fn inner(&self) -> &Arc<crate::Certificate> {
-        &self.inner
+    // crl distribution point urls, full names only
+    pub fn parse_crl_distribution_points(certificate: &crate::Certificate) -> Vec<Url> {
+        certificate
+            .tbs_certificate
+            .get::<CrlDistributionPoints>()
+            .ok()
+            .flatten()
+            .map_or_else(Vec::new, |(_, dps)| dps.0)
+            .into_iter()
+            .filter_map(|dp| match dp.distribution_point {
+                Some(DistributionPointName::FullName(names)) => Some(names),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|name| {
+                if let GeneralName::UniformResourceIdentifier(uri) = name {
+                    Url::parse(uri.as_str()).ok()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn issuer(&self) -> &str {
+        self.issuer.as_str()
+    }
+
+    pub fn subject(&self) -> &str {
+        self.subject.as_str()
+    }
+
+    pub fn authority_key_identifier(&self) -> Option<&[u8]> {
+        self.authority_key_identifier.as_deref()
+    }
+
+    pub fn parse_authority_key_identifier(certificate: &crate::Certificate) -> Option<Vec<u8>> {
+        certificate
+            .tbs_certificate
+            .get::<AuthorityKeyIdentifier>()
+            .ok()
+            .flatten()
+            .and_then(|(_, aki)| aki.key_identifier)
+            .map(|k| k.into_bytes())
+    }
+
+    // sha-256 of der encoding
+    pub fn fingerprint(&self) -> &[u8] {
+        self.fingerprint.as_slice()
     }
 
-    pub fn set_ord(&mut self, ord: usize) {
-        self.ord = ord;
+    pub fn inner(&self) -> &Arc<crate::Certificate> {
+        &self.inner
     }
 
     pub fn origin(&self) -> &CertificateOrigin {
@@ -88,56 +149,39 @@ impl<'r> Decode<'r> for Certificate {
     fn decode<R: Reader<'r>>(reader: &mut R) -> der::Result<Self> {
         let header = Header::decode(reader)?;
         let inner = Arc::new(crate::Certificate::decode_value(reader, header)?);
-        Ok(inner.into())
+        inner.try_into()
     }
 }
 
+// identity is the full der encoding, compared by fingerprint
 impl PartialEq for Certificate {
     fn eq(&self, other: &Self) -> bool {
-        self.inner.eq(&other.inner)
+        self.fingerprint == other.fingerprint && self.inner.eq(&other.inner)
     }
 }
 
 impl Eq for Certificate {}
 
-impl PartialOrd for Certificate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Certificate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.eq(other) {
-            return Ordering::Equal;
-        }
-
-        if self.ord > other.ord {
-            return Ordering::Greater;
-        }
-
-        Ordering::Less
-    }
-}
-
 impl Hash for Certificate {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.hash.hash(state)
+        self.fingerprint.hash(state)
     }
 }
 
-impl From<Arc<crate::Certificate>> for Certificate {
-    fn from(inner: Arc<crate::Certificate>) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(inner.signature.raw_bytes());
-        Self {
+// fingerprint needs the der encoding, so certificates that can't be encoded are refused
+impl TryFrom<Arc<crate::Certificate>> for Certificate {
+    type Error = der::Error;
+
+    fn try_from(inner: Arc<crate::Certificate>) -> der::Result<Self> {
+        let fingerprint = Sha256::digest(inner.to_der()?).to_vec();
+        Ok(Self {
             issuer: inner.tbs_certificate.issuer.to_string(),
             subject: inner.tbs_certificate.subject.to_string(),
             aia: Self::parse_aia(&inner),
+            authority_key_identifier: Self::parse_authority_key_identifier(&inner),
             inner,
-            ord: 0,
-            hash: hasher.finalize().to_vec(),
+            fingerprint,
             origin: CertificateOrigin::Unknown,
-        }
+        })
     }
 }
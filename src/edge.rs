@@ -17,7 +17,8 @@ pub enum Edge {
 pub struct Edges {
     visited: HashSet<Edge>,
     parents: HashMap<Edge, Edge>,
-    edges: Vec<Edge>,
+    // queued edges with their parent, recorded when dequeued as an edge can be queued under several parents
+    edges: Vec<(Edge, Option<Edge>)>,
 }
 
 impl Edges {
@@ -29,13 +30,23 @@ impl Edges {
         }
     }
 
+    // reset search state, so a finder can be reused across searches
     pub fn start(&mut self, mut certificate: Certificate) {
+        self.visited.clear();
+        self.parents.clear();
+        self.edges.clear();
         certificate.set_origin(CertificateOrigin::Target);
-        self.edges.push(Edge::Certificate(certificate.into()))
+        self.edges
+            .push((Edge::Certificate(certificate.into()), None))
     }
 
     pub fn next(&mut self) -> Option<Edge> {
-        self.edges.pop()
+        let (edge, parent) = self.edges.pop()?;
+        match parent {
+            Some(parent) => self.parents.insert(edge.clone(), parent),
+            None => self.parents.remove(&edge),
+        };
+        Some(edge)
     }
 
     // extend edge queue while preventing duplicates in path
@@ -49,8 +60,7 @@ impl Edges {
                     continue;
                 }
             }
-            self.edges.push(child.clone());
-            self.parents.insert(child, parent.clone());
+            self.edges.push((child, Some(parent.clone())));
         }
     }
 
@@ -68,21 +78,31 @@ impl Edges {
         path
     }
 
-    pub fn path(&self, target: &Edge) -> (Vec<Arc<crate::Certificate>>, Vec<CertificateOrigin>) {
+    pub fn path(
+        &self,
+        target: &Edge,
+    ) -> (
+        Vec<Arc<crate::Certificate>>,
+        Vec<CertificateOrigin>,
+        Vec<Vec<u8>>,
+    ) {
         let mut path = vec![];
         let mut path_origin = vec![];
+        let mut path_fingerprints = vec![];
 
         let mut current_edge = Some(target);
         while let Some(edge) = current_edge {
             if let Edge::Certificate(certificate) = &edge {
                 path.push(certificate.inner().clone());
                 path_origin.push(certificate.origin().clone());
+                path_fingerprints.push(certificate.fingerprint().to_vec());
             }
             current_edge = self.parents.get(edge);
         }
         path.reverse();
         path_origin.reverse();
-        (path, path_origin)
+        path_fingerprints.reverse();
+        (path, path_origin, path_fingerprints)
     }
 
     pub fn visit(&mut self, edge: Edge) {
@@ -1,27 +1,29 @@
-use crate::api::{CertificatePathValidation, PathValidator};
+use crate::anchor::TrustAnchor;
+use crate::api::{AsyncPathValidator, CertificateBackend, CertificatePathValidation, Evidence};
 use crate::certificate::Certificate;
 use crate::edge::{Edge, Edges};
-use crate::report::{CertificateOrigin, Found, Report, ValidationFailure};
-use crate::store::CertificateStore;
+use crate::report::{CertificateOrigin, Expiry, Found, Report, ValidationFailure};
+use crate::store::{MemoryStore, SelfSignedPolicy};
 use crate::{X509PathFinderError, X509PathFinderResult};
 #[cfg(test)]
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::vec;
 use url::Url;
 use x509_client::X509ClientResult;
 #[cfg(not(test))]
 use {
-    x509_client::provided::default::DefaultX509Iterator,
+    crate::load::CertificateIterator,
     x509_client::{X509Client, X509ClientConfiguration},
 };
 
-/// [`X509PathFinder`](crate::X509PathFinder) configuration
-#[derive(Clone)]
+/// [`X509PathFinder`](crate::X509PathFinder) configuration. Start from [`new`](Self::new) and set fields with struct
+/// update syntax, so fields added later take their defaults.
+#[derive(Clone, Default)]
 pub struct X509PathFinderConfiguration<V>
 where
-    V: PathValidator,
+    V: AsyncPathValidator,
 {
     /// limit runtime of path search. Actual limit will be N * HTTP timeout. See `Reqwest` docs for setting HTTP connection timeout.
     pub limit: Duration,
@@ -30,37 +32,98 @@ where
     pub aia: Option<X509ClientConfiguration>,
     #[cfg(test)]
     pub aia: Option<TestAIA>,
-    /// [`PathValidator`](crate::api::PathValidator) implementation
+    /// [`PathValidator`](crate::api::PathValidator) or [`AsyncPathValidator`](crate::api::AsyncPathValidator) implementation
     pub validator: V,
     /// Bridge and cross signed-certificates to use for path finding
     pub certificates: Vec<Arc<crate::Certificate>>,
+    /// Trust anchors. Paths whose top certificate is issued by an anchor are validated before searching further.
+    pub anchors: Vec<TrustAnchor>,
+    /// Prefer paths remaining valid beyond this horizon. Valid paths expiring sooner are reported in
+    /// [`Report::expiring`](crate::report::Report::expiring) while the search continues, and the first is only found
+    /// if no longer lived path is. `Duration::ZERO` finds the first valid path.
+    pub horizon: Duration,
+    /// Include self-signed certificates from `certificates`, the store and AIA URLs as the final element of paths, for
+    /// validators expecting the root in the path. Paths ending at an anchor are still validated without the root if
+    /// paths through it fail. Excluded by default.
+    pub self_signed: SelfSignedPolicy,
+}
+
+impl<V> X509PathFinderConfiguration<V>
+where
+    V: AsyncPathValidator,
+{
+    /// Configuration with `validator`, without a time limit, AIA, certificates, anchors or horizon, and with
+    /// self-signed certificates excluded
+    pub fn new(validator: V) -> Self {
+        Self {
+            limit: Duration::ZERO,
+            aia: None,
+            validator,
+            certificates: vec![],
+            anchors: vec![],
+            horizon: Duration::ZERO,
+            self_signed: SelfSignedPolicy::Exclude,
+        }
+    }
 }
 
 /// X509 Path Finder
-pub struct X509PathFinder<V>
+pub struct X509PathFinder<V, S = MemoryStore>
 where
-    V: PathValidator,
+    V: AsyncPathValidator,
+    S: CertificateBackend,
 {
     limit: Duration,
     #[cfg(not(test))]
-    aia: Option<X509Client<DefaultX509Iterator>>,
+    aia: Option<X509Client<CertificateIterator>>,
     #[cfg(test)]
     aia: Option<TestAIA>,
     validator: V,
-    store: CertificateStore,
+    store: S,
+    // configured certificates not yet inserted into store
+    pending: Vec<Arc<crate::Certificate>>,
+    anchors: Vec<TrustAnchor>,
+    horizon: Duration,
+    self_signed: SelfSignedPolicy,
     edges: Edges,
 }
 
 impl<V> X509PathFinder<V>
 where
-    V: PathValidator,
-    X509PathFinderError: From<<V as PathValidator>::PathValidatorError>,
+    V: AsyncPathValidator,
+    X509PathFinderError: From<<V as AsyncPathValidator>::PathValidatorError>,
 {
-    /// Instantiate new X509PathFinder with configuration
+    /// Instantiate new X509PathFinder with configuration, searching a [`MemoryStore`] of the configured `certificates`
     pub fn new(config: X509PathFinderConfiguration<V>) -> Self
     where
         X509PathFinderError: From<der::Error>,
     {
+        let mut store = MemoryStore::new();
+        store.set_self_signed(config.self_signed);
+        for certificate in config.certificates.iter() {
+            store.insert(certificate.clone());
+        }
+        Self::with_store(
+            X509PathFinderConfiguration {
+                certificates: vec![],
+                ..config
+            },
+            store,
+        )
+    }
+}
+
+impl<V, S> X509PathFinder<V, S>
+where
+    V: AsyncPathValidator,
+    S: CertificateBackend,
+    X509PathFinderError: From<<V as AsyncPathValidator>::PathValidatorError>,
+    X509PathFinderError: From<<S as CertificateBackend>::CertificateBackendError>,
+{
+    /// Instantiate new X509PathFinder with configuration, searching `store` for issuer candidates. The configured
+    /// `certificates` are inserted into `store` when the first search starts. When including self-signed certificates,
+    /// configure `store` to store them too.
+    pub fn with_store(config: X509PathFinderConfiguration<V>, store: S) -> Self {
         X509PathFinder {
             limit: config.limit,
             #[cfg(not(test))]
@@ -68,43 +131,111 @@ where
             #[cfg(test)]
             aia: config.aia,
             validator: config.validator,
-            store: CertificateStore::from_iter(config.certificates.into_iter().map(|c| c.into())),
+            store,
+            pending: config.certificates,
+            anchors: config.anchors,
+            horizon: config.horizon,
+            self_signed: config.self_signed,
             edges: Edges::new(),
         }
     }
 
+    /// Certificate store searched for issuer candidates, including certificates cached from AIA URLs
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Mutable certificate store, to add or remove certificates between searches
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
     /// Find certificate path, returning [`Report`](crate::report::Report)
     pub async fn find<I: Into<Arc<crate::Certificate>>>(
         &mut self,
         target: I,
     ) -> X509PathFinderResult<Report> {
+        self.find_with_evidence(target, Evidence::default()).await
+    }
+
+    /// Find certificate path with out-of-band `evidence`, such as a stapled OCSP response, returning
+    /// [`Report`](crate::report::Report). Evidence is passed to the validator, and any revocation evidence that proved
+    /// the path not revoked is reported in [`Found`](crate::report::Found).
+    pub async fn find_with_evidence<I: Into<Arc<crate::Certificate>>, E: Into<Evidence>>(
+        &mut self,
+        target: I,
+        evidence: E,
+    ) -> X509PathFinderResult<Report> {
+        let evidence: Evidence = evidence.into();
+        let mut pending = std::mem::take(&mut self.pending).into_iter();
+        while let Some(certificate) = pending.next() {
+            if let Err(e) = self.store.insert(certificate.clone()).await {
+                // retried by the next search
+                self.pending = std::iter::once(certificate).chain(pending).collect();
+                return Err(e.into());
+            }
+        }
+
         let target: Arc<crate::Certificate> = target.into();
-        self.edges.start(target.into());
+        self.edges.start(Certificate::try_from(target)?);
         let start = Instant::now();
         let mut failures = vec![];
+        let mut vetoes = vec![];
+        let mut expiring = vec![];
+        // first valid path expiring within horizon, found if no longer lived path is
+        let mut fallback: Option<Found> = None;
+        let cache = self.validator.cache_counters();
 
         while let Some(edge) = self.edges.next() {
             if self.limit != Duration::ZERO && Instant::now() - start > self.limit {
-                return Err(X509PathFinderError::Error("limit exceeded".to_string()));
+                if fallback.is_none() {
+                    return Err(X509PathFinderError::Error("limit exceeded".to_string()));
+                }
+                break;
             }
 
             if edge == Edge::End {
-                let (path, origin) = self.edges.path(&edge);
+                let (path, origin, fingerprints) = self.edges.path(&edge);
                 match self
                     .validator
-                    .validate(path.iter().map(|c| c.as_ref()).collect())?
+                    .validate_with_evidence(path.iter().map(|c| c.as_ref()).collect(), &evidence)
+                    .await?
                 {
-                    CertificatePathValidation::Found => {
-                        return Ok(Report {
-                            found: Some(Found { path, origin }),
-                            duration: Instant::now() - start,
-                            failures,
-                        });
+                    (CertificatePathValidation::Found, revocation) => {
+                        let found = Found {
+                            expiry: self.expiry(path.as_slice()),
+                            path,
+                            origin,
+                            fingerprints,
+                            revocation,
+                        };
+
+                        if !found.expiry.within_horizon {
+                            // passed over fallback expires within horizon too
+                            if let Some(fallback) = fallback.take() {
+                                expiring.insert(0, self.expiring(fallback));
+                            }
+
+                            return Ok(Report {
+                                found: Some(found),
+                                duration: Instant::now() - start,
+                                failures,
+                                vetoes,
+                                cache: self.validator.cache_counters() - cache,
+                                expiring,
+                            });
+                        }
+
+                        match fallback {
+                            Some(_) => expiring.push(self.expiring(found)),
+                            None => fallback = Some(found),
+                        }
                     }
-                    CertificatePathValidation::NotFound(reason) => {
+                    (CertificatePathValidation::NotFound(reason), _) => {
                         failures.push(ValidationFailure {
                             path,
                             origin,
+                            fingerprints,
                             reason,
                         });
                     }
@@ -117,24 +248,109 @@ where
 
             self.edges.visit(edge.clone());
 
-            self.next(edge).await?;
+            self.next(edge, &mut vetoes).await?;
         }
 
         Ok(Report {
-            found: None,
+            found: fallback,
             duration: Instant::now() - start,
             failures,
+            vetoes,
+            cache: self.validator.cache_counters() - cache,
+            expiring,
         })
     }
 
-    async fn next(&mut self, edge: Edge) -> X509PathFinderResult<()> {
+    /// Blocking [`find`](crate::X509PathFinder::find), for callers without an async runtime. Requires the `blocking`
+    /// feature.
+    #[cfg(feature = "blocking")]
+    pub fn find_blocking<I: Into<Arc<crate::Certificate>>>(
+        &mut self,
+        target: I,
+    ) -> X509PathFinderResult<Report> {
+        self.find_with_evidence_blocking(target, Evidence::default())
+    }
+
+    /// Blocking [`find_with_evidence`](crate::X509PathFinder::find_with_evidence), for callers without an async runtime.
+    /// Requires the `blocking` feature.
+    ///
+    /// The search runs on a private single-threaded runtime, which can't be started from within another async runtime,
+    /// so calling from within one returns an error. Synchronous callbacks of an async runtime, such as rustls
+    /// verifiers, should search on another thread instead.
+    #[cfg(feature = "blocking")]
+    pub fn find_with_evidence_blocking<I: Into<Arc<crate::Certificate>>, E: Into<Evidence>>(
+        &mut self,
+        target: I,
+        evidence: E,
+    ) -> X509PathFinderResult<Report> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err(X509PathFinderError::Error(
+                "blocking search called within an async runtime".to_string(),
+            ));
+        }
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| X509PathFinderError::Error(e.to_string()))?
+            .block_on(self.find_with_evidence(target, evidence))
+    }
+
+    // earliest expiry across path, checked against horizon
+    fn expiry(&self, path: &[Arc<crate::Certificate>]) -> Expiry {
+        let (certificate, not_after) = path
+            .iter()
+            .map(|c| c.tbs_certificate.validity.not_after.to_system_time())
+            .enumerate()
+            .min_by_key(|(_, not_after)| *not_after)
+            .unwrap_or((0, SystemTime::UNIX_EPOCH));
+
+        Expiry {
+            not_after,
+            certificate,
+            within_horizon: self.horizon != Duration::ZERO
+                && not_after < SystemTime::now() + self.horizon,
+        }
+    }
+
+    // valid path passed over for expiring within horizon
+    fn expiring(&self, found: Found) -> ValidationFailure {
+        let reason = format!(
+            "valid but expiring within {} days: {}",
+            self.horizon.as_secs() / 86400,
+            found.path[found.expiry.certificate].tbs_certificate.subject
+        );
+        ValidationFailure {
+            path: found.path,
+            origin: found.origin,
+            fingerprints: found.fingerprints,
+            reason,
+        }
+    }
+
+    async fn next(
+        &mut self,
+        edge: Edge,
+        vetoes: &mut Vec<ValidationFailure>,
+    ) -> X509PathFinderResult<()> {
         match &edge {
+            // edge is included self-signed certificate, end path
+            Edge::Certificate(edge_certificate)
+                if self.self_signed == SelfSignedPolicy::Include
+                    && edge_certificate.issued(edge_certificate) =>
+            {
+                self.edges.extend(edge.clone(), vec![Edge::End]);
+                Ok(())
+            }
             // edge is leaf certificate, search for issuer candidates
             Edge::Certificate(edge_certificate) => {
-                let mut store_candidates = self.next_store(edge_certificate.clone());
+                let store_candidates = self.next_store(edge_certificate.clone()).await?;
+                let mut store_candidates = self
+                    .check_links(&edge, edge_certificate, store_candidates, vetoes)
+                    .await?;
 
                 // queue issuer candidates from store or try aia
-                if !store_candidates.is_empty() {
+                let mut candidates = if !store_candidates.is_empty() {
                     // queue any aia edges
                     store_candidates.extend(
                         edge_certificate
@@ -145,18 +361,40 @@ where
 
                     // reverse store edges so explored by store priority
                     store_candidates.reverse();
-                    self.edges.extend(edge.clone(), store_candidates);
-                    Ok(())
+                    store_candidates
                 } else {
-                    self.edges
-                        .extend(edge.clone(), self.next_aia(edge_certificate.clone()));
-                    Ok(())
+                    self.next_aia(edge_certificate.clone())
+                };
+
+                // issued by trust anchor, queue end edge last so path is validated before searching further. included
+                // self-signed candidates are queued after it, so paths through them are validated first, and the path
+                // ending here still is if they fail
+                if !candidates.contains(&Edge::End)
+                    && self.anchors.iter().any(|a| a.issued(edge_certificate))
+                {
+                    let (self_signed, mut others): (Vec<Edge>, Vec<Edge>) =
+                        candidates.into_iter().partition(Self::self_signed);
+                    others.push(Edge::End);
+                    others.extend(self_signed);
+                    candidates = others;
                 }
+
+                self.edges.extend(edge.clone(), candidates);
+                Ok(())
             }
             // edge is url, download certificates, queue issuer candidates
             Edge::Url(url, edge_certificate) => {
-                let url_edges = self.next_url(edge_certificate.as_ref(), url).await;
-                self.edges.extend(edge, url_edges);
+                let url_candidates = self.next_url(edge_certificate.as_ref(), url).await?;
+                let url_candidates = self
+                    .check_links(&edge, edge_certificate, url_candidates, vetoes)
+                    .await?;
+
+                // no issuer candidates, queue end edge
+                if url_candidates.is_empty() {
+                    self.edges.extend(edge, vec![Edge::End]);
+                } else {
+                    self.edges.extend(edge, url_candidates);
+                }
                 Ok(())
             }
             // edge is end, stop search
@@ -164,41 +402,95 @@ where
         }
     }
 
-    // return issuer candidates from store
-    fn next_store(&self, parent_certificate: Arc<Certificate>) -> Vec<Edge> {
-        self.store
-            .issuers(parent_certificate.as_ref())
-            .into_iter()
-            .map(Edge::Certificate)
-            .collect()
+    fn self_signed(edge: &Edge) -> bool {
+        matches!(edge, Edge::Certificate(c) if c.issued(c))
     }
 
-    // download certificates, insert into store, return non self-signed issuer candidates
-    async fn next_url(&mut self, parent_certificate: &Certificate, url: &Url) -> Vec<Edge> {
-        let candidates = self
-            .get_all(url)
-            .await
-            .unwrap_or_else(|_| vec![])
+    // return issuer candidates from store, without self-signed unless included
+    async fn next_store(
+        &self,
+        parent_certificate: Arc<Certificate>,
+    ) -> X509PathFinderResult<Vec<Edge>> {
+        Ok(self
+            .store
+            .issuers(parent_certificate.inner())
+            .await?
             .into_iter()
-            .filter_map(|candidate| {
-                // filtering out self-signed
-                self.store.insert(candidate).and_then(|candidate| {
-                    // url is issuer, return as certificate edge
-                    candidate
-                        .issued(parent_certificate)
-                        .then(|| Edge::Certificate(candidate))
-                })
+            .map(|(c, origin)| {
+                let mut c = Certificate::try_from(c)?;
+                c.set_origin(origin);
+                Ok(Edge::Certificate(Arc::new(c)))
             })
-            // reverse certificates so explored in order returned from server
-            .rev()
-            .collect::<Vec<Edge>>();
+            .collect::<X509PathFinderResult<Vec<Edge>>>()?
+            .into_iter()
+            .filter(|e| self.self_signed == SelfSignedPolicy::Include || !Self::self_signed(e))
+            .collect())
+    }
 
-        // no issuer candidates, return end edge
-        if candidates.is_empty() {
-            vec![Edge::End]
-        } else {
-            candidates
+    // drop issuer candidates vetoed by validator, recording vetoes
+    async fn check_links(
+        &self,
+        parent: &Edge,
+        child: &Certificate,
+        candidates: Vec<Edge>,
+        vetoes: &mut Vec<ValidationFailure>,
+    ) -> X509PathFinderResult<Vec<Edge>> {
+        let mut checked = vec![];
+        for candidate in candidates.into_iter() {
+            if let Edge::Certificate(issuer) = &candidate {
+                if let CertificatePathValidation::NotFound(reason) = self
+                    .validator
+                    .check_link(child.inner(), issuer.inner())
+                    .await?
+                {
+                    let (mut path, mut origin, mut fingerprints) = self.edges.path(parent);
+                    path.push(issuer.inner().clone());
+                    origin.push(issuer.origin().clone());
+                    fingerprints.push(issuer.fingerprint().to_vec());
+                    vetoes.push(ValidationFailure {
+                        path,
+                        origin,
+                        fingerprints,
+                        reason,
+                    });
+                    continue;
+                }
+            }
+            checked.push(candidate);
         }
+        Ok(checked)
+    }
+
+    // download certificates, insert into store, return issuer candidates, without self-signed unless included
+    async fn next_url(
+        &mut self,
+        parent_certificate: &Certificate,
+        url: &Url,
+    ) -> X509PathFinderResult<Vec<Edge>> {
+        let mut candidates = vec![];
+        for candidate in self.get_all(url).await.unwrap_or_else(|_| vec![]) {
+            // filtering out self-signed
+            if self.self_signed == SelfSignedPolicy::Exclude && candidate.issued(&candidate) {
+                continue;
+            }
+            // a store failing to keep the download doesn't fail the search, the candidate is still explored
+            if let Err(e) = self
+                .store
+                .insert_from_url(candidate.inner().clone(), url)
+                .await
+            {
+                log::warn!("certificate downloaded from {} not stored: {}", url, e);
+            }
+
+            // url is issuer, return as certificate edge
+            if candidate.issued(parent_certificate) {
+                candidates.push(Edge::Certificate(Arc::new(candidate)));
+            }
+        }
+
+        // reverse certificates so explored in order returned from server
+        candidates.reverse();
+        Ok(candidates)
     }
 
     // if aia enabled, return aia edges
@@ -230,8 +522,9 @@ where
                 .get_all(url)
                 .await?
                 .into_iter()
-                .map(|c| {
-                    let mut c = Certificate::from(Arc::new(c));
+                // skipping certificates that can't be encoded, so can't be fingerprinted
+                .filter_map(|c| Certificate::try_from(Arc::new(c)).ok())
+                .map(|mut c| {
                     c.set_origin(CertificateOrigin::Url(url.clone()));
                     c
                 })
@@ -250,8 +543,8 @@ where
             return Ok(aia
                 .certificates
                 .get(url)
-                .map_or_else(std::vec::Vec::new, |c| {
-                    let mut c = Certificate::from(c.clone());
+                .and_then(|c| Certificate::try_from(c.clone()).ok())
+                .map_or_else(std::vec::Vec::new, |mut c| {
                     c.set_origin(CertificateOrigin::Url(url.clone()));
                     vec![c]
                 }));
@@ -1,13 +1,17 @@
 #![doc = include_str!("../README.md")]
 
+pub mod anchor;
 pub mod api;
 mod certificate;
 mod edge;
 mod find;
+pub mod load;
 pub mod provided;
 pub mod report;
 mod result;
-mod store;
+#[cfg(feature = "rustls-webpki")]
+mod signature;
+pub mod store;
 
 pub use find::*;
 pub use result::*;
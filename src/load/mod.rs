@@ -0,0 +1,326 @@
+//! Certificate loaders, producing [`X509PathFinderConfiguration::certificates`](crate::X509PathFinderConfiguration::certificates)
+//! from PEM bundles, DER files, PKCS#7 bundles and directories
+
+pub mod result;
+
+use crate::load::result::{LoadError, LoadResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+#[cfg(feature = "pkcs7")]
+use cms::{cert::CertificateChoices, content_info::ContentInfo, signed_data::SignedData};
+#[cfg(feature = "pkcs7")]
+use der::oid::db::rfc5911::ID_SIGNED_DATA;
+use der::{Decode, Encode};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, vec};
+use x509_client::api::X509Iterator;
+
+/// Loaded certificates, in the order found, and any malformed entries skipped along the way
+#[derive(Clone, Debug, Default)]
+pub struct Loaded {
+    /// Parsed certificates
+    pub certificates: Vec<Arc<crate::Certificate>>,
+    /// Skipped entries
+    pub skipped: Vec<Skipped>,
+}
+
+impl Loaded {
+    fn extend(&mut self, other: Loaded) {
+        self.certificates.extend(other.certificates);
+        self.skipped.extend(other.skipped);
+    }
+}
+
+/// Malformed entry skipped while loading
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Skipped {
+    /// File the entry was read from, if loaded from the file system
+    pub path: Option<PathBuf>,
+    /// Index of the PEM block within its bundle, if the entry is a PEM block
+    pub block: Option<usize>,
+    /// Reason the entry was skipped
+    pub reason: String,
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+        if let Some(block) = self.block {
+            write!(f, "block {}: ", block)?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Load PEM bundle of `CERTIFICATE` blocks, and `PKCS7` or `CMS` blocks of certs-only bundles. Text between blocks,
+/// such as comments, is ignored. Malformed blocks, and blocks with other labels, are skipped.
+pub fn from_pem(data: &[u8]) -> Loaded {
+    let mut loaded = Loaded::default();
+    let skip = |block: usize, reason: String| Skipped {
+        path: None,
+        block: Some(block),
+        reason,
+    };
+
+    // label and base64 body of open block
+    let mut open: Option<(String, String)> = None;
+    let mut block = 0;
+
+    for line in String::from_utf8_lossy(data).lines() {
+        let line = line.trim();
+        if let Some(label) = boundary(line, "BEGIN") {
+            if open.is_some() {
+                loaded
+                    .skipped
+                    .push(skip(block, "unterminated pem block".to_string()));
+                block += 1;
+            }
+            open = Some((label.to_string(), String::new()));
+        } else if let Some(label) = boundary(line, "END") {
+            if let Some((begin, body)) = open.take() {
+                match pem_block(begin.as_str(), label, body.as_str()) {
+                    Ok(certificates) => loaded.certificates.extend(certificates),
+                    Err(reason) => loaded.skipped.push(skip(block, reason)),
+                }
+                block += 1;
+            }
+        } else if let Some((_, body)) = open.as_mut() {
+            // skip rfc 1421 encapsulated headers
+            if !line.contains(':') {
+                body.push_str(line);
+            }
+        }
+    }
+
+    if open.is_some() {
+        loaded
+            .skipped
+            .push(skip(block, "unterminated pem block".to_string()));
+    }
+
+    loaded
+}
+
+/// Load single DER encoded certificate
+pub fn from_der(data: &[u8]) -> LoadResult<Arc<crate::Certificate>> {
+    Ok(Arc::new(crate::Certificate::from_der(data)?))
+}
+
+/// Load certificates from DER encoded PKCS#7 / CMS certs-only `SignedData` bundle, such as a `.p7b` or `.p7c` file.
+/// Certificate choices other than X509 certificates are ignored. Available with the `pkcs7` feature.
+#[cfg(feature = "pkcs7")]
+pub fn from_pkcs7(data: &[u8]) -> LoadResult<Vec<Arc<crate::Certificate>>> {
+    let content_info = ContentInfo::from_der(data)?;
+    if content_info.content_type != ID_SIGNED_DATA {
+        return Err(LoadError::Error(format!(
+            "unsupported cms content type: {}",
+            content_info.content_type
+        )));
+    }
+    let signed_data = SignedData::from_der(content_info.content.to_der()?.as_slice())?;
+
+    Ok(signed_data
+        .certificates
+        .map_or_else(Vec::new, |c| c.0.into_vec())
+        .into_iter()
+        .filter_map(|c| match c {
+            CertificateChoices::Certificate(c) => Some(Arc::new(c)),
+            CertificateChoices::Other(_) => None,
+        })
+        .collect())
+}
+
+// bundles aren't decoded without the `pkcs7` feature
+#[cfg(not(feature = "pkcs7"))]
+fn from_pkcs7(_data: &[u8]) -> LoadResult<Vec<Arc<crate::Certificate>>> {
+    Err(LoadError::Error(
+        "pkcs7 requires the `pkcs7` feature".to_string(),
+    ))
+}
+
+/// Load PEM bundle, DER certificate or DER PKCS#7 bundle from file at `path`. Files containing a PEM boundary are loaded
+/// as PEM, others as DER. A malformed DER file is skipped.
+pub fn from_file<P: AsRef<Path>>(path: P) -> LoadResult<Loaded> {
+    let path = path.as_ref();
+    let data = fs::read(path)?;
+
+    let mut loaded = if is_pem(data.as_slice()) {
+        from_pem(data.as_slice())
+    } else {
+        match from_der(data.as_slice())
+            .map(|c| vec![c])
+            .or_else(|_| from_pkcs7(data.as_slice()))
+        {
+            Ok(certificates) => Loaded {
+                certificates,
+                skipped: vec![],
+            },
+            Err(e) => Loaded {
+                certificates: vec![],
+                skipped: vec![Skipped {
+                    path: None,
+                    block: None,
+                    reason: format!("neither pem, der nor pkcs7: {}", e),
+                }],
+            },
+        }
+    };
+
+    for skipped in loaded.skipped.iter_mut() {
+        skipped.path = Some(path.to_path_buf());
+    }
+    Ok(loaded)
+}
+
+/// Load every file in directory at `path`, in file name order. Subdirectories are not searched. Symbolic links are
+/// followed, and certificates already loaded are not loaded again, so OpenSSL `c_rehash` layouts of hashed-name links
+/// alongside the original files load each certificate once. Hashed-name CRL links are ignored. Unreadable and
+/// malformed files are skipped.
+pub fn from_directory<P: AsRef<Path>>(path: P) -> LoadResult<Loaded> {
+    let mut paths = fs::read_dir(path)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<PathBuf>, std::io::Error>>()?;
+    paths.sort();
+
+    let mut loaded = Loaded::default();
+    let mut files = HashSet::new();
+    let mut encodings = HashSet::new();
+
+    for path in paths {
+        if path.is_dir() || is_hashed_crl(path.as_path()) {
+            continue;
+        }
+
+        // link to file already loaded
+        if let Ok(canonical) = fs::canonicalize(&path) {
+            if !files.insert(canonical) {
+                continue;
+            }
+        }
+
+        match from_file(&path) {
+            Ok(mut file) => {
+                file.certificates
+                    .retain(|c| c.to_der().map_or(true, |der| encodings.insert(der)));
+                loaded.extend(file);
+            }
+            Err(e) => loaded.skipped.push(Skipped {
+                path: Some(path),
+                block: None,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(loaded)
+}
+
+// label of pem boundary line of kind BEGIN or END
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")
+}
+
+fn pem_block(begin: &str, end: &str, body: &str) -> Result<Vec<Arc<crate::Certificate>>, String> {
+    if begin != end {
+        return Err(format!("mismatched pem labels: {} and {}", begin, end));
+    }
+    let decode = || {
+        STANDARD
+            .decode(body)
+            .map_err(|e| format!("invalid base64: {}", e))
+    };
+    match begin {
+        "CERTIFICATE" => from_der(decode()?.as_slice())
+            .map(|c| vec![c])
+            .map_err(|e| format!("invalid certificate: {}", e)),
+        // openssl writes PKCS7, rfc 7468 specifies CMS
+        "PKCS7" | "CMS" => {
+            from_pkcs7(decode()?.as_slice()).map_err(|e| format!("invalid pkcs7 bundle: {}", e))
+        }
+        _ => Err(format!("unsupported pem label: {}", begin)),
+    }
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.windows(10).any(|w| w == b"-----BEGIN")
+}
+
+// c_rehash crl link, named hash.rN
+fn is_hashed_crl(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.split_once(".r"))
+        .is_some_and(|(hash, n)| {
+            hash.len() == 8
+                && hash.chars().all(|c| c.is_ascii_hexdigit())
+                && !n.is_empty()
+                && n.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// [`X509Iterator`](https://docs.rs/x509-client/2.0.1/x509_client/api/trait.X509Iterator.html) decoding with the
+/// loaders of this module. [`X509PathFinder`](crate::X509PathFinder) decodes AIA responses with it, so `caIssuers`
+/// endpoints publishing PKCS#7 bundles in PEM form are admitted, as well as DER certificates, PEM bundles and DER PKCS#7
+/// bundles. Responses decoded as a certificate fall back to bundles, for endpoints serving bundles with a certificate
+/// content type or URL extension.
+pub struct CertificateIterator(Vec<crate::Certificate>);
+
+impl IntoIterator for CertificateIterator {
+    type Item = crate::Certificate;
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<crate::Certificate> for CertificateIterator {
+    fn from_iter<T: IntoIterator<Item = crate::Certificate>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl X509Iterator for CertificateIterator {
+    type X509IteratorError = LoadError;
+
+    fn from_cer<T: AsRef<[u8]>>(src: T) -> LoadResult<Self> {
+        match crate::Certificate::from_der(src.as_ref()) {
+            Ok(certificate) => Ok(Self(vec![certificate])),
+            // bundles served as certificates, reporting the certificate error if not a bundle either
+            Err(e) => Self::from_pkcs7(src).map_err(|_| e.into()),
+        }
+    }
+
+    fn from_pem<T: AsRef<[u8]>>(src: T) -> LoadResult<Self> {
+        let loaded = from_pem(src.as_ref());
+        // nothing but malformed blocks
+        if loaded.certificates.is_empty() {
+            if let Some(skipped) = loaded.skipped.first() {
+                return Err(LoadError::Error(skipped.to_string()));
+            }
+        }
+        Ok(loaded
+            .certificates
+            .iter()
+            .map(|c| c.as_ref().clone())
+            .collect())
+    }
+
+    fn from_pkcs7<T: AsRef<[u8]>>(src: T) -> LoadResult<Self> {
+        if is_pem(src.as_ref()) {
+            return Self::from_pem(src);
+        }
+        Ok(from_pkcs7(src.as_ref())?
+            .iter()
+            .map(|c| c.as_ref().clone())
+            .collect())
+    }
+}
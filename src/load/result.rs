@@ -0,0 +1,56 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+use x509_client::api::X509IteratorError;
+use x509_client::X509ClientError;
+
+pub type LoadResult<T> = result::Result<T, LoadError>;
+
+/// Errors when loading certificates
+#[derive(Debug)]
+pub enum LoadError {
+    /// General errors
+    Error(String),
+    /// Errors when reading a file or directory
+    IoError(std::io::Error),
+    /// Errors when parsing a certificate
+    DerError(der::Error),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Error(e) => {
+                write!(f, "load -> error: {}", e)
+            }
+            LoadError::IoError(e) => {
+                write!(f, "load -> io error: {}", e)
+            }
+            LoadError::DerError(e) => {
+                write!(f, "load -> der error: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+impl From<der::Error> for LoadError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl X509IteratorError for LoadError {}
+
+impl From<LoadError> for X509ClientError {
+    fn from(e: LoadError) -> Self {
+        Self::X509IteratorError(Box::new(e))
+    }
+}
@@ -0,0 +1,89 @@
+//! HTTP [`Fetcher`] implementation
+
+pub mod result;
+
+use crate::api::{Fetcher, FetcherError};
+use crate::provided::fetcher::http::result::{HttpFetcherError, HttpFetcherResult};
+use async_trait::async_trait;
+use url::Url;
+use x509_client::provided::debug::DebugX509Iterator;
+use x509_client::reqwest::header::CONTENT_TYPE;
+use x509_client::reqwest::{Client, Response};
+use x509_client::{X509Client, X509ClientConfiguration};
+
+/// HTTP [`Fetcher`], reusing the AIA [`X509Client`](https://docs.rs/x509-client/2.0.1/x509_client/struct.X509Client.html) transport
+#[derive(Clone, Default)]
+pub struct HttpFetcher {
+    client: X509Client<DebugX509Iterator>,
+    http_client: Client,
+    limit: Option<usize>,
+}
+
+impl HttpFetcher {
+    /// Constructor takes the same [`X509ClientConfiguration`](https://docs.rs/x509-client/2.0.1/x509_client/struct.X509ClientConfiguration.html)
+    /// used for AIA. Downloads go through an `X509Client` sharing its HTTP client, `files` and `limit` settings.
+    pub fn new(config: &X509ClientConfiguration) -> Self {
+        let http_client = config.http_client.clone().unwrap_or_default();
+        Self {
+            client: X509Client::new(X509ClientConfiguration {
+                // downloads aren't certificates, take the bytes as-is
+                strict: false,
+                http_client: Some(http_client.clone()),
+                ..config.clone()
+            }),
+            http_client,
+            limit: config.limit,
+        }
+    }
+
+    async fn read(&self, resp: Response) -> HttpFetcherResult<Vec<u8>> {
+        let mut resp = resp.error_for_status()?;
+
+        let mut buf = vec![];
+        while let Some(b) = resp.chunk().await? {
+            buf.extend(b);
+            if let Some(limit) = self.limit {
+                if buf.len() > limit {
+                    return Err(HttpFetcherError::Error(format!(
+                        "total transferred bytes {} exceeded limit {}",
+                        buf.len(),
+                        limit
+                    )));
+                }
+            }
+        }
+        Ok(buf)
+    }
+}
+
+#[async_trait]
+impl Fetcher for HttpFetcher {
+    type FetcherError = HttpFetcherError;
+
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, Self::FetcherError> {
+        Ok(self.client.get(url).await?.to_vec())
+    }
+
+    async fn post(
+        &self,
+        url: &Url,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>, Self::FetcherError> {
+        self.read(
+            self.http_client
+                .post(url.as_str())
+                .header(CONTENT_TYPE, content_type)
+                .body(body)
+                .send()
+                .await?,
+        )
+        .await
+    }
+}
+
+impl FetcherError for HttpFetcherError {
+    fn unsupported(operation: &str, url: &Url) -> Self {
+        Self::Error(format!("{} not supported: {}", operation, url))
+    }
+}
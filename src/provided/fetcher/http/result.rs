@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+use x509_client::{reqwest, X509ClientError};
+
+pub type HttpFetcherResult<T> = result::Result<T, HttpFetcherError>;
+
+#[derive(Debug)]
+pub enum HttpFetcherError {
+    Error(String),
+    ClientError(X509ClientError),
+    ReqwestError(reqwest::Error),
+}
+
+impl Display for HttpFetcherError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpFetcherError::Error(e) => {
+                write!(f, "http fetcher -> error: {}", e)
+            }
+            HttpFetcherError::ClientError(e) => {
+                write!(f, "http fetcher -> {}", e)
+            }
+            HttpFetcherError::ReqwestError(e) => {
+                write!(f, "http fetcher -> reqwest error: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for HttpFetcherError {}
+
+impl From<X509ClientError> for HttpFetcherError {
+    fn from(e: X509ClientError) -> Self {
+        Self::ClientError(e)
+    }
+}
+
+impl From<reqwest::Error> for HttpFetcherError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::ReqwestError(e)
+    }
+}
@@ -0,0 +1,3 @@
+//! [`Fetcher`](crate::api::Fetcher) implementations
+
+pub mod http;
@@ -1,3 +1,5 @@
 //! Provided implementations
 
+pub mod fetcher;
 pub mod validator;
+pub mod verifier;
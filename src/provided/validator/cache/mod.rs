@@ -0,0 +1,425 @@
+//! Caching [`PathValidator`] and [`AsyncPathValidator`] implementations
+
+use crate::api::{
+    AsyncPathValidator, CertificatePathValidation, Evidence, PathValidator, RevocationEvidence,
+};
+use crate::report::CacheCounters;
+use async_trait::async_trait;
+use der::Encode;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Kind {
+    Validate,
+    // fingerprint of supplied evidence
+    Evidence(Vec<u8>),
+    Link,
+}
+
+// certificate fingerprints, and the time bucket validation occurred in
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Key {
+    kind: Kind,
+    fingerprints: Vec<Vec<u8>>,
+    bucket: u64,
+}
+
+// validation, and evidence it used
+type Validated = (CertificatePathValidation, Vec<RevocationEvidence>);
+
+struct Entry {
+    validated: Validated,
+    inserted: Instant,
+    used: u64,
+}
+
+struct Cache {
+    entries: HashMap<Key, Entry>,
+    // least recently used first
+    recency: BTreeMap<u64, Key>,
+    tick: u64,
+    counters: CacheCounters,
+}
+
+impl Cache {
+    fn get(&mut self, key: &Key, ttl: Duration) -> Option<Validated> {
+        let entry = self.entries.get_mut(key)?;
+
+        if ttl != Duration::ZERO && Instant::now() - entry.inserted > ttl {
+            self.recency.remove(&entry.used);
+            self.entries.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        self.recency.remove(&entry.used);
+        self.recency.insert(self.tick, key.clone());
+        entry.used = self.tick;
+        Some(entry.validated.clone())
+    }
+
+    fn insert(&mut self, key: Key, validated: Validated, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+
+        if let Some(entry) = self.entries.remove(&key) {
+            self.recency.remove(&entry.used);
+        }
+
+        // evict least recently used
+        while self.entries.len() >= capacity {
+            match self.recency.pop_first() {
+                Some((_, evicted)) => self.entries.remove(&evicted),
+                None => break,
+            };
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                validated,
+                inserted: Instant::now(),
+                used: self.tick,
+            },
+        );
+    }
+}
+
+// cached validator, its results and cache configuration, shared by the sync and async validators
+struct Cached<V> {
+    validator: V,
+    capacity: usize,
+    ttl: Duration,
+    bucket: Duration,
+    cache: Mutex<Cache>,
+}
+
+impl<V> Cached<V> {
+    fn new(validator: V, capacity: usize, ttl: Duration, bucket: Duration) -> Self {
+        Self {
+            validator,
+            capacity,
+            ttl,
+            bucket,
+            cache: Mutex::new(Cache {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                tick: 0,
+                counters: CacheCounters::default(),
+            }),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn clear(&self) {
+        let mut cache = self.lock();
+        cache.entries.clear();
+        cache.recency.clear();
+    }
+
+    fn counters(&self) -> CacheCounters {
+        self.lock().counters
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // key is none if a certificate can't be encoded, bypassing the cache. bucketed by validation time `now`
+    fn key(&self, kind: Kind, path: &[&crate::Certificate], now: SystemTime) -> Option<Key> {
+        let mut fingerprints = vec![];
+        for certificate in path {
+            fingerprints.push(Sha256::digest(certificate.to_der().ok()?).to_vec());
+        }
+
+        let bucket = if self.bucket == Duration::ZERO {
+            0
+        } else {
+            let now = now.duration_since(UNIX_EPOCH).ok()?;
+            (now.as_nanos() / self.bucket.as_nanos()) as u64
+        };
+
+        Some(Key {
+            kind,
+            fingerprints,
+            bucket,
+        })
+    }
+
+    fn validate_key(&self, path: &[&crate::Certificate]) -> Option<Key> {
+        self.key(Kind::Validate, path, SystemTime::now())
+    }
+
+    fn evidence_key(&self, path: &[&crate::Certificate], evidence: &Evidence) -> Option<Key> {
+        self.key(
+            evidence_kind(evidence),
+            path,
+            evidence.time.unwrap_or_else(SystemTime::now),
+        )
+    }
+
+    fn link_key(&self, child: &crate::Certificate, issuer: &crate::Certificate) -> Option<Key> {
+        self.key(Kind::Link, &[child, issuer], SystemTime::now())
+    }
+
+    // counts a hit or miss, lock is not held while validating a miss
+    fn get(&self, key: &Option<Key>) -> Option<Validated> {
+        let key = key.as_ref()?;
+        let mut cache = self.lock();
+        match cache.get(key, self.ttl) {
+            Some(validated) => {
+                cache.counters.hits += 1;
+                Some(validated)
+            }
+            None => {
+                cache.counters.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&self, key: Option<Key>, validated: &Validated) {
+        if let Some(key) = key {
+            self.lock().insert(key, validated.clone(), self.capacity);
+        }
+    }
+
+    fn cached<F, E>(&self, key: Option<Key>, f: F) -> Result<Validated, E>
+    where
+        F: FnOnce() -> Result<Validated, E>,
+    {
+        if let Some(validated) = self.get(&key) {
+            return Ok(validated);
+        }
+
+        let validated = f()?;
+        self.insert(key, &validated);
+        Ok(validated)
+    }
+}
+
+// supplied evidence varies between searches, so it's part of the key
+fn evidence_kind(evidence: &Evidence) -> Kind {
+    let mut hasher = Sha256::new();
+    let revocation = evidence.revocation.iter().map(|e| match e {
+        RevocationEvidence::Ocsp(der) => (0u8, der),
+        RevocationEvidence::Crl(der) => (1u8, der),
+    });
+    for (tag, der) in revocation.chain(evidence.scts.iter().map(|list| (2u8, list))) {
+        hasher.update([tag]);
+        hasher.update((der.len() as u64).to_be_bytes());
+        hasher.update(der);
+    }
+    Kind::Evidence(hasher.finalize().to_vec())
+}
+
+/// Caches results of the wrapped [`PathValidator`], so paths and links shared across
+/// searches are validated once. Results are keyed by the SHA-256 fingerprints of the certificates and by time bucket,
+/// and held in a bounded least-recently-used cache. Results of
+/// [`validate_with_evidence`](crate::api::PathValidator::validate_with_evidence) are also keyed by the supplied
+/// evidence, and cached together with the evidence they used. Errors are not cached. See
+/// [`AsyncCachePathValidator`] to cache an
+/// [`AsyncPathValidator`].
+pub struct CachePathValidator<V>
+where
+    V: PathValidator,
+{
+    cached: Cached<V>,
+}
+
+impl<V> CachePathValidator<V>
+where
+    V: PathValidator,
+{
+    /// Constructor takes the validator to cache, and:
+    /// * `capacity` is the maximum number of cached results. Least recently used results are evicted first.
+    /// * `ttl` is the time a result is cached for. `Duration::ZERO` caches until evicted.
+    /// * `bucket` is the width of the validation time bucket, results are only reused within the same bucket.
+    ///   `Duration::ZERO` reuses results regardless of validation time.
+    pub fn new(validator: V, capacity: usize, ttl: Duration, bucket: Duration) -> Self {
+        Self {
+            cached: Cached::new(validator, capacity, ttl, bucket),
+        }
+    }
+
+    /// Cached validator
+    pub fn validator(&self) -> &V {
+        &self.cached.validator
+    }
+
+    /// Number of cached results
+    pub fn len(&self) -> usize {
+        self.cached.len()
+    }
+
+    /// Cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.cached.is_empty()
+    }
+
+    /// Remove all cached results
+    pub fn clear(&self) {
+        self.cached.clear()
+    }
+}
+
+impl<V> PathValidator for CachePathValidator<V>
+where
+    V: PathValidator,
+{
+    type PathValidatorError = V::PathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        let key = self.cached.validate_key(path.as_slice());
+        self.cached
+            .cached(key, || Ok((self.cached.validator.validate(path)?, vec![])))
+            .map(|(validation, _)| validation)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let key = self.cached.evidence_key(path.as_slice(), evidence);
+        self.cached.cached(key, || {
+            self.cached.validator.validate_with_evidence(path, evidence)
+        })
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        let key = self.cached.link_key(child, issuer);
+        self.cached
+            .cached(key, || {
+                Ok((self.cached.validator.check_link(child, issuer)?, vec![]))
+            })
+            .map(|(validation, _)| validation)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.cached.counters() + self.cached.validator.cache_counters()
+    }
+}
+
+/// Caches results of the wrapped [`AsyncPathValidator`], such as a revocation
+/// checking validator. Behaves as [`CachePathValidator`].
+pub struct AsyncCachePathValidator<V>
+where
+    V: AsyncPathValidator,
+{
+    cached: Cached<V>,
+}
+
+impl<V> AsyncCachePathValidator<V>
+where
+    V: AsyncPathValidator,
+{
+    /// Constructor takes the validator to cache. See [`CachePathValidator::new`].
+    pub fn new(validator: V, capacity: usize, ttl: Duration, bucket: Duration) -> Self {
+        Self {
+            cached: Cached::new(validator, capacity, ttl, bucket),
+        }
+    }
+
+    /// Cached validator
+    pub fn validator(&self) -> &V {
+        &self.cached.validator
+    }
+
+    /// Number of cached results
+    pub fn len(&self) -> usize {
+        self.cached.len()
+    }
+
+    /// Cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.cached.is_empty()
+    }
+
+    /// Remove all cached results
+    pub fn clear(&self) {
+        self.cached.clear()
+    }
+}
+
+#[async_trait]
+impl<V> AsyncPathValidator for AsyncCachePathValidator<V>
+where
+    V: AsyncPathValidator + Sync,
+{
+    type PathValidatorError = V::PathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        let key = self.cached.validate_key(path.as_slice());
+        if let Some((validation, _)) = self.cached.get(&key) {
+            return Ok(validation);
+        }
+
+        let validation = self.cached.validator.validate(path).await?;
+        self.cached.insert(key, &(validation.clone(), vec![]));
+        Ok(validation)
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let key = self.cached.evidence_key(path.as_slice(), evidence);
+        if let Some(validated) = self.cached.get(&key) {
+            return Ok(validated);
+        }
+
+        let validated = self
+            .cached
+            .validator
+            .validate_with_evidence(path, evidence)
+            .await?;
+        self.cached.insert(key, &validated);
+        Ok(validated)
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        let key = self.cached.link_key(child, issuer);
+        if let Some((validation, _)) = self.cached.get(&key) {
+            return Ok(validation);
+        }
+
+        let validation = self.cached.validator.check_link(child, issuer).await?;
+        self.cached.insert(key, &(validation.clone(), vec![]));
+        Ok(validation)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.cached.counters() + self.cached.validator.cache_counters()
+    }
+}
@@ -0,0 +1,624 @@
+//! Composite [`PathValidator`] and [`AsyncPathValidator`] implementations
+//!
+//! Combine multiple validators, with differing error types, into a single [`PathValidator`]:
+//! * [`AllPathValidator`] - path is valid if every validator finds it valid
+//! * [`AnyPathValidator`] - path is valid if at least one validator finds it valid
+//! * [`FallbackPathValidator`] - first validator to return without error decides
+//!
+//! [`AsyncAllPathValidator`],
+//! [`AsyncAnyPathValidator`] and
+//! [`AsyncFallbackPathValidator`] combine
+//! [`AsyncPathValidator`] implementations, such as revocation checking validators, in the same way.
+
+pub mod result;
+
+use crate::api::{
+    AsyncPathValidator, CertificatePathValidation, Evidence, PathValidator, PathValidatorError,
+    RevocationEvidence,
+};
+use crate::provided::validator::composite::result::{
+    CompositePathValidatorError, CompositePathValidatorResult,
+};
+use crate::provided::validator::dynamic::result::DynPathValidatorResult;
+use crate::provided::validator::dynamic::{DynAsyncPathValidator, DynPathValidator};
+use crate::report::CacheCounters;
+use async_trait::async_trait;
+
+// validation, and revocation evidence proving a found path not revoked
+type Validated = (CertificatePathValidation, Vec<RevocationEvidence>);
+
+fn merge(reasons: Vec<String>) -> String {
+    reasons.join("; ")
+}
+
+// validator method the composite delegates to
+enum Call<'c> {
+    Validate(Vec<&'c crate::Certificate>),
+    Evidence(Vec<&'c crate::Certificate>, &'c Evidence),
+    Link(&'c crate::Certificate, &'c crate::Certificate),
+}
+
+impl<'c> Call<'c> {
+    fn call(&self, validator: &DynPathValidator) -> DynPathValidatorResult<Validated> {
+        match self {
+            Call::Validate(path) => Ok((PathValidator::validate(validator, path.clone())?, vec![])),
+            Call::Evidence(path, evidence) => {
+                PathValidator::validate_with_evidence(validator, path.clone(), evidence)
+            }
+            Call::Link(child, issuer) => {
+                Ok((PathValidator::check_link(validator, child, issuer)?, vec![]))
+            }
+        }
+    }
+
+    async fn call_async(
+        &self,
+        validator: &DynAsyncPathValidator<'_>,
+    ) -> DynPathValidatorResult<Validated> {
+        match self {
+            Call::Validate(path) => Ok((validator.validate(path.clone()).await?, vec![])),
+            Call::Evidence(path, evidence) => {
+                validator
+                    .validate_with_evidence(path.clone(), evidence)
+                    .await
+            }
+            Call::Link(child, issuer) => Ok((validator.check_link(child, issuer).await?, vec![])),
+        }
+    }
+}
+
+// how validator results are combined
+#[derive(Clone, Copy)]
+enum Mode {
+    All,
+    Any,
+    Fallback,
+}
+
+// combines validator results in order
+struct Fold {
+    mode: Mode,
+    reasons: Vec<String>,
+    errors: Vec<String>,
+    revocation: Vec<RevocationEvidence>,
+}
+
+impl Fold {
+    fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            reasons: vec![],
+            errors: vec![],
+            revocation: vec![],
+        }
+    }
+
+    // some if the result is decided, and remaining validators aren't run
+    fn step(
+        &mut self,
+        result: DynPathValidatorResult<Validated>,
+    ) -> Option<CompositePathValidatorResult<Validated>> {
+        match (self.mode, result) {
+            (Mode::All | Mode::Any, Err(e)) => Some(Err(e.into())),
+            (Mode::All, Ok((CertificatePathValidation::Found, evidence))) => {
+                for e in evidence {
+                    if !self.revocation.contains(&e) {
+                        self.revocation.push(e);
+                    }
+                }
+                None
+            }
+            (Mode::Any, Ok((CertificatePathValidation::Found, evidence))) => {
+                Some(Ok((CertificatePathValidation::Found, evidence)))
+            }
+            (Mode::All | Mode::Any, Ok((CertificatePathValidation::NotFound(reason), _))) => {
+                self.reasons.push(reason);
+                None
+            }
+            (Mode::Fallback, Ok(validation)) => Some(Ok(validation)),
+            (Mode::Fallback, Err(e)) => {
+                self.errors.push(e.to_string());
+                None
+            }
+        }
+    }
+
+    fn finish(self) -> CompositePathValidatorResult<Validated> {
+        match self.mode {
+            Mode::All if self.reasons.is_empty() => {
+                Ok((CertificatePathValidation::Found, self.revocation))
+            }
+            Mode::All | Mode::Any => Ok((
+                CertificatePathValidation::NotFound(merge(self.reasons)),
+                vec![],
+            )),
+            Mode::Fallback => Err(CompositePathValidatorError::Error(merge(self.errors))),
+        }
+    }
+}
+
+fn no_validators() -> CompositePathValidatorResult<Validated> {
+    Ok((
+        CertificatePathValidation::NotFound("no validators".to_string()),
+        vec![],
+    ))
+}
+
+// validators and how their results are combined, shared by the sync and async composites
+struct Composite<V> {
+    mode: Mode,
+    validators: Vec<V>,
+}
+
+impl<V> Composite<V> {
+    fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            validators: vec![],
+        }
+    }
+}
+
+impl<'v> Composite<DynPathValidator<'v>> {
+    fn push<V>(&mut self, validator: V)
+    where
+        V: PathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.validators.push(DynPathValidator::new(validator));
+    }
+
+    fn fold(&self, call: Call) -> CompositePathValidatorResult<Validated> {
+        if self.validators.is_empty() {
+            return no_validators();
+        }
+
+        let mut fold = Fold::new(self.mode);
+        for validator in self.validators.iter() {
+            if let Some(result) = fold.step(call.call(validator)) {
+                return result;
+            }
+        }
+        fold.finish()
+    }
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> CompositePathValidatorResult<CertificatePathValidation> {
+        self.fold(Call::Validate(path))
+            .map(|(validation, _)| validation)
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> CompositePathValidatorResult<CertificatePathValidation> {
+        self.fold(Call::Link(child, issuer))
+            .map(|(validation, _)| validation)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validators
+            .iter()
+            .fold(CacheCounters::default(), |c, v| {
+                c + PathValidator::cache_counters(v)
+            })
+    }
+}
+
+impl<'v> Composite<DynAsyncPathValidator<'v>> {
+    fn push<V>(&mut self, validator: V)
+    where
+        V: AsyncPathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.validators.push(DynAsyncPathValidator::new(validator));
+    }
+
+    async fn fold(&self, call: Call<'_>) -> CompositePathValidatorResult<Validated> {
+        if self.validators.is_empty() {
+            return no_validators();
+        }
+
+        let mut fold = Fold::new(self.mode);
+        for validator in self.validators.iter() {
+            if let Some(result) = fold.step(call.call_async(validator).await) {
+                return result;
+            }
+        }
+        fold.finish()
+    }
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> CompositePathValidatorResult<CertificatePathValidation> {
+        self.fold(Call::Validate(path))
+            .await
+            .map(|(validation, _)| validation)
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> CompositePathValidatorResult<CertificatePathValidation> {
+        self.fold(Call::Link(child, issuer))
+            .await
+            .map(|(validation, _)| validation)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validators
+            .iter()
+            .fold(CacheCounters::default(), |c, v| c + v.cache_counters())
+    }
+}
+
+/// Path is valid if every validator finds it valid. All validators are run, so failure reasons are merged.
+pub struct AllPathValidator<'v> {
+    inner: Composite<DynPathValidator<'v>>,
+}
+
+impl<'v> AllPathValidator<'v> {
+    /// Instantiate with no validators. An empty `AllPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::All),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: PathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for AllPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'v> PathValidator for AllPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence))
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// Path is valid if at least one validator finds it valid. Validators are run in order, stopping at the first success.
+pub struct AnyPathValidator<'v> {
+    inner: Composite<DynPathValidator<'v>>,
+}
+
+impl<'v> AnyPathValidator<'v> {
+    /// Instantiate with no validators. An empty `AnyPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::Any),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: PathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for AnyPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'v> PathValidator for AnyPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence))
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// Validators are run in order. The first validator to return without error decides the result,
+/// later validators are only consulted when earlier ones fail with an error.
+pub struct FallbackPathValidator<'v> {
+    inner: Composite<DynPathValidator<'v>>,
+}
+
+impl<'v> FallbackPathValidator<'v> {
+    /// Instantiate with no validators. An empty `FallbackPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::Fallback),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: PathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for FallbackPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'v> PathValidator for FallbackPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence))
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// Path is valid if every validator finds it valid. See [`AllPathValidator`].
+pub struct AsyncAllPathValidator<'v> {
+    inner: Composite<DynAsyncPathValidator<'v>>,
+}
+
+impl<'v> AsyncAllPathValidator<'v> {
+    /// Instantiate with no validators. An empty `AsyncAllPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::All),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: AsyncPathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for AsyncAllPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<'v> AsyncPathValidator for AsyncAllPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path).await
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence)).await
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer).await
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// Path is valid if at least one validator finds it valid. See [`AnyPathValidator`].
+pub struct AsyncAnyPathValidator<'v> {
+    inner: Composite<DynAsyncPathValidator<'v>>,
+}
+
+impl<'v> AsyncAnyPathValidator<'v> {
+    /// Instantiate with no validators. An empty `AsyncAnyPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::Any),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: AsyncPathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for AsyncAnyPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<'v> AsyncPathValidator for AsyncAnyPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path).await
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence)).await
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer).await
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// First validator to return without error decides. See [`FallbackPathValidator`].
+pub struct AsyncFallbackPathValidator<'v> {
+    inner: Composite<DynAsyncPathValidator<'v>>,
+}
+
+impl<'v> AsyncFallbackPathValidator<'v> {
+    /// Instantiate with no validators. An empty `AsyncFallbackPathValidator` never finds a path valid.
+    pub fn new() -> Self {
+        Self {
+            inner: Composite::new(Mode::Fallback),
+        }
+    }
+
+    /// Append `validator`
+    pub fn push<V>(&mut self, validator: V)
+    where
+        V: AsyncPathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        self.inner.push(validator);
+    }
+}
+
+impl<'v> Default for AsyncFallbackPathValidator<'v> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<'v> AsyncPathValidator for AsyncFallbackPathValidator<'v> {
+    type PathValidatorError = CompositePathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path).await
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<Validated, Self::PathValidatorError> {
+        self.inner.fold(Call::Evidence(path, evidence)).await
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer).await
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+impl PathValidatorError for CompositePathValidatorError {}
@@ -0,0 +1,49 @@
+use crate::api::PathValidatorError;
+use crate::provided::validator::dynamic::result::DynPathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type CompositePathValidatorResult<T> = result::Result<T, CompositePathValidatorError>;
+
+#[derive(Debug)]
+pub enum CompositePathValidatorError {
+    Error(String),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for CompositePathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompositePathValidatorError::Error(e) => {
+                write!(f, "composite path validator -> error: {}", e)
+            }
+            CompositePathValidatorError::PathValidatorError(e) => {
+                write!(f, "composite path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for CompositePathValidatorError {}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for CompositePathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<DynPathValidatorError> for CompositePathValidatorError {
+    fn from(e: DynPathValidatorError) -> Self {
+        match e {
+            DynPathValidatorError::PathValidatorError(e) => Self::PathValidatorError(e),
+        }
+    }
+}
+
+impl From<CompositePathValidatorError> for X509PathFinderError {
+    fn from(e: CompositePathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
@@ -0,0 +1,353 @@
+//! CRL revocation checking [`AsyncPathValidator`] implementation
+
+pub mod result;
+
+use crate::anchor::TrustAnchor;
+use crate::api::{
+    AsyncPathValidator, CertificatePathValidation, Evidence, Fetcher, PathValidatorError,
+    RevocationEvidence,
+};
+use crate::certificate::Certificate;
+use crate::provided::validator::crl::result::{CrlPathValidatorError, CrlPathValidatorResult};
+use crate::provided::validator::revocation::{self, Checked};
+use crate::report::CacheCounters;
+use async_trait::async_trait;
+use der::{Decode, EncodeValue};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+use url::Url;
+use webpki::{
+    BorrowedCertRevocationList, CertRevocationList, OwnedCertRevocationList, SignatureAlgorithm,
+};
+use x509_cert::crl::CertificateList;
+use x509_cert::ext::pkix::KeyUsage;
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+
+struct Crl {
+    der: Vec<u8>,
+    crl: OwnedCertRevocationList,
+    issuer: String,
+    next_update: SystemTime,
+}
+
+impl Crl {
+    fn parse(der: &[u8]) -> Result<Self, String> {
+        let list = CertificateList::from_der(der).map_err(|e| e.to_string())?;
+        let next_update = list
+            .tbs_cert_list
+            .next_update
+            .ok_or_else(|| "crl has no next update".to_string())?
+            .to_system_time();
+        let crl = BorrowedCertRevocationList::from_der(der)
+            .and_then(|c| c.to_owned())
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            der: der.to_vec(),
+            crl,
+            issuer: list.tbs_cert_list.issuer.to_string(),
+            next_update,
+        })
+    }
+
+    // certificate listed as revoked
+    fn listed(&self, certificate: &crate::Certificate) -> Result<bool, String> {
+        self.crl
+            .find_serial(certificate.tbs_certificate.serial_number.as_bytes())
+            .map(|r| r.is_some())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Checks revocation of paths found valid by the wrapped validator, with CRLs fetched from each certificate's
+/// [CRL Distribution Points](https://datatracker.ietf.org/doc/html/rfc5280#section-4.2.1.13).
+///
+/// CRLs supplied as [`RevocationEvidence`] are used before fetching any. Otherwise, CRLs
+/// are fetched with a [`Fetcher`], and cached until their `nextUpdate`.
+/// A CRL is only used once its issuer and signature are verified against the issuing certificate in the path,
+/// or against a trust anchor for the top certificate. Issuing certificates with a key usage extension must assert
+/// `cRLSign`. Certificates without distribution points are not checked.
+/// A path is rejected when a certificate is revoked, or when none of its CRLs can be fetched and verified.
+///
+/// CRLs are treated as complete CRLs covering every certificate of their issuer. Distribution point scope is not
+/// matched: a CRL's issuing distribution point extension is not compared with the certificate's distribution points,
+/// and indirect and delta CRLs are not supported.
+pub struct CrlPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator,
+    F: Fetcher,
+{
+    validator: V,
+    fetcher: F,
+    algorithms: &'a [&'a SignatureAlgorithm],
+    anchors: Vec<TrustAnchor>,
+    cache: Mutex<HashMap<Url, Arc<Crl>>>,
+}
+
+impl<'a, V, F> CrlPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator,
+    F: Fetcher,
+{
+    /// Constructor takes the validator to wrap, and:
+    /// * `fetcher` to fetch CRLs with
+    /// * `algorithms` is the list of signature algorithms trusted for CRL signatures
+    /// * `anchors` are trust anchors, used to verify CRLs of the top certificate in a path
+    pub fn new(
+        validator: V,
+        fetcher: F,
+        algorithms: &'a [&'a SignatureAlgorithm],
+        anchors: Vec<TrustAnchor>,
+    ) -> Self {
+        Self {
+            validator,
+            fetcher,
+            algorithms,
+            anchors,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Url, Arc<Crl>>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn verify(
+        &self,
+        crl: &Crl,
+        certificate: &crate::Certificate,
+        keys: &[Vec<u8>],
+    ) -> Result<(), String> {
+        if crl.issuer != certificate.tbs_certificate.issuer.to_string() {
+            return Err(format!("crl issued by {}", crl.issuer));
+        }
+        if !keys
+            .iter()
+            .any(|k| crl.crl.verify_signature(self.algorithms, k).is_ok())
+        {
+            return Err("crl signature not verified".to_string());
+        }
+        Ok(())
+    }
+
+    // cached crl if fresh, otherwise fetched. only verified crls are cached
+    async fn crl(
+        &self,
+        url: &Url,
+        certificate: &crate::Certificate,
+        keys: &[Vec<u8>],
+    ) -> Result<Arc<Crl>, String> {
+        let now = SystemTime::now();
+
+        let cached = self.lock().get(url).cloned();
+        if let Some(crl) = cached {
+            if crl.next_update > now {
+                self.verify(&crl, certificate, keys)?;
+                return Ok(crl);
+            }
+        }
+
+        let der = self.fetcher.fetch(url).await.map_err(|e| e.to_string())?;
+        let crl = Arc::new(Crl::parse(der.as_slice())?);
+        self.verify(&crl, certificate, keys)?;
+        if crl.next_update <= now {
+            return Err("crl expired".to_string());
+        }
+
+        self.lock().insert(url.clone(), crl.clone());
+        Ok(crl)
+    }
+
+    // reason path is rejected, if any. crls proving certificates not revoked are appended to `used`
+    async fn revoked(
+        &self,
+        path: &[&crate::Certificate],
+        evidence: &Evidence,
+        used: &mut Vec<RevocationEvidence>,
+    ) -> CrlPathValidatorResult<Option<String>> {
+        let supplied = evidence
+            .revocation
+            .iter()
+            .filter_map(|e| match e {
+                RevocationEvidence::Crl(der) => Crl::parse(der.as_slice()).ok(),
+                _ => None,
+            })
+            .collect::<Vec<Crl>>();
+        let supplied = supplied.as_slice();
+
+        revocation::check_path(
+            path,
+            self.anchors.as_slice(),
+            used,
+            move |certificate, issuer, keys| self.check(certificate, issuer, keys, supplied),
+        )
+        .await
+    }
+
+    // revocation status of certificate, from supplied crls before fetching any. `crl_issuer` is the issuing
+    // certificate in the path, none for the top certificate
+    async fn check(
+        &self,
+        certificate: &crate::Certificate,
+        crl_issuer: Option<&crate::Certificate>,
+        keys: Vec<SubjectPublicKeyInfoOwned>,
+        supplied: &[Crl],
+    ) -> CrlPathValidatorResult<Checked<String>> {
+        let now = SystemTime::now();
+        let urls = Certificate::parse_crl_distribution_points(certificate);
+        let issuer = certificate.tbs_certificate.issuer.to_string();
+        let supplied = supplied
+            .iter()
+            .filter(|c| c.issuer == issuer)
+            .collect::<Vec<&Crl>>();
+        if urls.is_empty() && supplied.is_empty() {
+            return Ok(Checked::Skipped);
+        }
+
+        let subject = certificate.tbs_certificate.subject.to_string();
+        if keys.is_empty() {
+            return Ok(Checked::Rejected(format!(
+                "crl issuer not found: {}",
+                subject
+            )));
+        }
+        if let Some(crl_issuer) = crl_issuer {
+            if !crl_sign(crl_issuer)? {
+                return Ok(Checked::Rejected(format!(
+                    "crl issuer not permitted to sign crls: {}",
+                    crl_issuer.tbs_certificate.subject
+                )));
+            }
+        }
+        let keys = keys
+            .iter()
+            .map(spki_value)
+            .collect::<der::Result<Vec<Vec<u8>>>>()?;
+
+        let mut reasons = vec![];
+
+        for crl in supplied {
+            let listed = self
+                .verify(crl, certificate, keys.as_slice())
+                .and_then(|_| {
+                    if crl.next_update <= now {
+                        Err("crl expired".to_string())
+                    } else {
+                        crl.listed(certificate)
+                    }
+                });
+            match listed {
+                Ok(false) => return Ok(Checked::Good(RevocationEvidence::Crl(crl.der.clone()))),
+                Ok(true) => {
+                    return Ok(Checked::Rejected(format!(
+                        "certificate revoked: {}",
+                        subject
+                    )))
+                }
+                Err(reason) => reasons.push(format!("supplied crl: {}", reason)),
+            }
+        }
+
+        for url in urls.iter() {
+            let listed = match self.crl(url, certificate, keys.as_slice()).await {
+                Ok(crl) => crl.listed(certificate).map(|l| (l, crl)),
+                Err(reason) => Err(reason),
+            };
+            match listed {
+                Ok((false, crl)) => {
+                    return Ok(Checked::Good(RevocationEvidence::Crl(crl.der.clone())))
+                }
+                Ok((true, _)) => {
+                    return Ok(Checked::Rejected(format!(
+                        "certificate revoked: {}",
+                        subject
+                    )))
+                }
+                Err(reason) => reasons.push(format!("{}: {}", url, reason)),
+            }
+        }
+
+        Ok(Checked::Rejected(format!(
+            "revocation status unknown: {}: {}",
+            subject,
+            reasons.join("; ")
+        )))
+    }
+}
+
+#[async_trait]
+impl<'a, V, F> AsyncPathValidator for CrlPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator + Sync,
+    V::PathValidatorError: Send + Sync + 'static,
+    F: Fetcher,
+{
+    type PathValidatorError = CrlPathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self
+            .validate_with_evidence(path, &Evidence::default())
+            .await?
+            .0)
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let mut used = match self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .await
+            .map_err(|e| CrlPathValidatorError::PathValidatorError(Box::new(e)))?
+        {
+            (CertificatePathValidation::NotFound(reason), _) => {
+                return Ok((CertificatePathValidation::NotFound(reason), vec![]));
+            }
+            (CertificatePathValidation::Found, used) => used,
+        };
+
+        match self.revoked(path.as_slice(), evidence, &mut used).await? {
+            Some(reason) => Ok((CertificatePathValidation::NotFound(reason), vec![])),
+            None => Ok((CertificatePathValidation::Found, used)),
+        }
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .await
+            .map_err(|e| CrlPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+// issuer's key usage, if any, permits signing crls
+fn crl_sign(issuer: &crate::Certificate) -> der::Result<bool> {
+    match issuer.tbs_certificate.get::<KeyUsage>()? {
+        Some((_, usage)) => Ok(usage.crl_sign()),
+        None => Ok(true),
+    }
+}
+
+// webpki verifies signatures with the SPKI value, without its outer sequence
+fn spki_value(spki: &SubjectPublicKeyInfoOwned) -> der::Result<Vec<u8>> {
+    let mut value = vec![];
+    spki.encode_value(&mut value)?;
+    Ok(value)
+}
+
+impl PathValidatorError for CrlPathValidatorError {}
@@ -0,0 +1,46 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type CrlPathValidatorResult<T> = result::Result<T, CrlPathValidatorError>;
+
+#[derive(Debug)]
+pub enum CrlPathValidatorError {
+    DerError(der::Error),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for CrlPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrlPathValidatorError::DerError(e) => {
+                write!(f, "crl path validator -> der error: {}", e)
+            }
+            CrlPathValidatorError::PathValidatorError(e) => {
+                write!(f, "crl path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for CrlPathValidatorError {}
+
+impl From<der::Error> for CrlPathValidatorError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for CrlPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<CrlPathValidatorError> for X509PathFinderError {
+    fn from(e: CrlPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
@@ -0,0 +1,476 @@
+//! Certificate Transparency [`PathValidator`] implementation
+
+pub mod result;
+mod sct;
+
+use crate::anchor::TrustAnchor;
+use crate::api::{
+    CertificatePathValidation, Evidence, PathValidator, PathValidatorError, RevocationEvidence,
+};
+use crate::provided::validator::ct::result::{CtPathValidatorError, CtPathValidatorResult};
+use crate::provided::validator::ct::sct::{Entry, Sct};
+use crate::report::CacheCounters;
+use crate::signature;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use der::asn1::OctetString;
+use der::oid::db::rfc5912::{ECDSA_WITH_SHA_256, SHA_256_WITH_RSA_ENCRYPTION};
+use der::oid::ObjectIdentifier;
+use der::DateTime;
+use der::{Decode, Encode};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+
+// embedded SignedCertificateTimestampList extension, rfc 6962 section 3.3
+const ID_CE_SCT_LIST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.11129.2.4.2");
+
+#[derive(Deserialize)]
+struct LogListJson {
+    operators: Vec<OperatorJson>,
+}
+
+#[derive(Deserialize)]
+struct OperatorJson {
+    name: String,
+    logs: Vec<LogJson>,
+}
+
+#[derive(Deserialize)]
+struct LogJson {
+    #[serde(default)]
+    description: String,
+    key: String,
+    #[serde(default)]
+    state: Option<StateJson>,
+    #[serde(default)]
+    temporal_interval: Option<TemporalIntervalJson>,
+}
+
+// only the state a log is in matters
+#[derive(Deserialize)]
+struct StateJson {
+    #[serde(default)]
+    pending: Option<IgnoredAny>,
+    #[serde(default)]
+    retired: Option<IgnoredAny>,
+    #[serde(default)]
+    rejected: Option<IgnoredAny>,
+}
+
+impl StateJson {
+    fn trusted(&self) -> bool {
+        self.pending.is_none() && self.retired.is_none() && self.rejected.is_none()
+    }
+}
+
+#[derive(Deserialize)]
+struct TemporalIntervalJson {
+    start_inclusive: String,
+    end_exclusive: String,
+}
+
+/// Certificate Transparency log
+#[derive(Clone, Debug)]
+pub struct CtLog {
+    id: Vec<u8>,
+    key: SubjectPublicKeyInfoOwned,
+    description: String,
+    operator: String,
+    interval: Option<(SystemTime, SystemTime)>,
+}
+
+impl CtLog {
+    /// Constructor takes the log's DER encoded public key, and:
+    /// * `description` of the log
+    /// * `operator` is the name of the log operator
+    pub fn new(key: &[u8], description: &str, operator: &str) -> CtPathValidatorResult<Self> {
+        Ok(Self {
+            id: Sha256::digest(key).to_vec(),
+            key: SubjectPublicKeyInfoOwned::from_der(key)?,
+            description: description.to_string(),
+            operator: operator.to_string(),
+            interval: None,
+        })
+    }
+
+    /// Restrict the log to certificates expiring within `start` inclusive and `end` exclusive
+    pub fn with_temporal_interval(mut self, start: SystemTime, end: SystemTime) -> Self {
+        self.interval = Some((start, end));
+        self
+    }
+
+    /// Log ID, the SHA-256 hash of the log's public key
+    pub fn id(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+
+    /// Log description
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    /// Log operator
+    pub fn operator(&self) -> &str {
+        self.operator.as_str()
+    }
+
+    /// Expiry times of certificates the log accepts, start inclusive and end exclusive, if sharded
+    pub fn temporal_interval(&self) -> Option<(SystemTime, SystemTime)> {
+        self.interval
+    }
+
+    // log accepts certificates expiring at `not_after`
+    fn accepts(&self, not_after: SystemTime) -> bool {
+        !self
+            .interval
+            .is_some_and(|(start, end)| not_after < start || end <= not_after)
+    }
+}
+
+/// Trusted Certificate Transparency logs
+#[derive(Clone, Default, Debug)]
+pub struct CtLogList {
+    logs: Vec<CtLog>,
+}
+
+impl CtLogList {
+    /// Instantiate with `logs`
+    pub fn new(logs: Vec<CtLog>) -> Self {
+        Self { logs }
+    }
+
+    /// Load from a JSON file in the [log list v3](https://www.gstatic.com/ct/log_list/v3/log_list_schema.json) format
+    pub fn from_file<P: AsRef<Path>>(path: P) -> CtPathValidatorResult<Self> {
+        Self::from_json(std::fs::read_to_string(path)?.as_str())
+    }
+
+    /// Parse JSON in the [log list v3](https://www.gstatic.com/ct/log_list/v3/log_list_schema.json) format.
+    /// Pending, retired and rejected logs are skipped, and sharded logs only accept certificates expiring within
+    /// their `temporal_interval`.
+    pub fn from_json(json: &str) -> CtPathValidatorResult<Self> {
+        let list: LogListJson = serde_json::from_str(json)?;
+        let mut logs = vec![];
+        for operator in list.operators {
+            for log in operator.logs {
+                if log.state.as_ref().is_some_and(|state| !state.trusted()) {
+                    continue;
+                }
+                let error = |e: String| {
+                    CtPathValidatorError::Error(format!("log {}: {}", log.description, e))
+                };
+                let key = STANDARD
+                    .decode(log.key.as_bytes())
+                    .map_err(|e| error(e.to_string()))?;
+                let mut ct_log = CtLog::new(
+                    key.as_slice(),
+                    log.description.as_str(),
+                    operator.name.as_str(),
+                )?;
+                if let Some(interval) = &log.temporal_interval {
+                    ct_log = ct_log.with_temporal_interval(
+                        rfc3339(interval.start_inclusive.as_str()).map_err(error)?,
+                        rfc3339(interval.end_exclusive.as_str()).map_err(error)?,
+                    );
+                }
+                logs.push(ct_log);
+            }
+        }
+        Ok(Self { logs })
+    }
+
+    /// Trusted logs
+    pub fn logs(&self) -> &[CtLog] {
+        self.logs.as_slice()
+    }
+
+    fn log(&self, id: &[u8]) -> Option<&CtLog> {
+        self.logs.iter().find(|l| l.id == id)
+    }
+}
+
+/// Requires the target of paths found valid by the wrapped validator to carry
+/// [Signed Certificate Timestamps](https://datatracker.ietf.org/doc/html/rfc6962#section-3) from trusted logs.
+///
+/// SCTs embedded in the target are verified over its precertificate, reconstructed with the issuer found in the path,
+/// or a trust anchor when the target is the only certificate. SCTs supplied as
+/// [`Evidence::scts`](crate::api::Evidence::scts), such as from a TLS handshake, are verified over
+/// the target itself. A path is rejected unless SCTs from at least `minimum` distinct trusted logs verify.
+///
+/// The minimum counts logs, not log operators: logs run by the same operator each count, so unlike browser CT
+/// policies, operator diversity isn't required.
+pub struct CtPathValidator<V>
+where
+    V: PathValidator,
+{
+    validator: V,
+    logs: CtLogList,
+    minimum: usize,
+    anchors: Vec<TrustAnchor>,
+}
+
+impl<V> CtPathValidator<V>
+where
+    V: PathValidator,
+{
+    /// Constructor takes the validator to wrap, and:
+    /// * `logs` are the trusted CT logs
+    /// * `minimum` is the number of distinct logs, regardless of operator, the target must carry verified SCTs from
+    /// * `anchors` are trust anchors, used to reconstruct the precertificate when the target is the only certificate
+    pub fn new(validator: V, logs: CtLogList, minimum: usize, anchors: Vec<TrustAnchor>) -> Self {
+        Self {
+            validator,
+            logs,
+            minimum,
+            anchors,
+        }
+    }
+
+    // candidate issuer key hashes of the target
+    fn issuer_key_hashes(
+        &self,
+        path: &[&crate::Certificate],
+    ) -> CtPathValidatorResult<Vec<Vec<u8>>> {
+        let mut hashes = vec![];
+        match path.get(1) {
+            Some(issuer) => hashes.push(
+                Sha256::digest(issuer.tbs_certificate.subject_public_key_info.to_der()?).to_vec(),
+            ),
+            None => {
+                let issuer = path[0].tbs_certificate.issuer.to_string();
+                for anchor in self.anchors.iter().filter(|a| a.subject() == issuer) {
+                    hashes.push(Sha256::digest(anchor.spki()).to_vec());
+                }
+            }
+        }
+        Ok(hashes)
+    }
+
+    // log that issued the sct for `target`, once verified over any of `entries`
+    fn verify(
+        &self,
+        sct: &Sct,
+        target: &crate::Certificate,
+        entries: &[Entry],
+        now: u64,
+    ) -> Result<&CtLog, String> {
+        let log = self
+            .logs
+            .log(sct.log_id.as_slice())
+            .ok_or_else(|| format!("unknown log {}", STANDARD.encode(sct.log_id.as_slice())))?;
+        if !log.accepts(target.tbs_certificate.validity.not_after.to_system_time()) {
+            return Err(format!(
+                "{}: certificate expiry outside log temporal interval",
+                log.description
+            ));
+        }
+        if sct.version != 0 {
+            return Err(format!(
+                "{}: unsupported sct version {}",
+                log.description, sct.version
+            ));
+        }
+        if sct.timestamp > now {
+            return Err(format!("{}: sct timestamp in the future", log.description));
+        }
+
+        let algorithm = match (sct.hash_algorithm, sct.signature_algorithm) {
+            (4, 3) => ECDSA_WITH_SHA_256,
+            (4, 1) => SHA_256_WITH_RSA_ENCRYPTION,
+            (h, s) => {
+                return Err(format!(
+                    "{}: unsupported sct signature algorithm {}/{}",
+                    log.description, h, s
+                ))
+            }
+        };
+
+        if entries.iter().any(|entry| {
+            signature::verify(
+                signature::ALGORITHMS,
+                &log.key,
+                &algorithm,
+                sct.signed_data(entry).as_slice(),
+                sct.signature.as_slice(),
+            )
+            .is_ok()
+        }) {
+            Ok(log)
+        } else {
+            Err(format!("{}: sct signature not verified", log.description))
+        }
+    }
+
+    // reason path is rejected, if any
+    fn unqualified(
+        &self,
+        path: &[&crate::Certificate],
+        evidence: &Evidence,
+    ) -> CtPathValidatorResult<Option<String>> {
+        let target = match path.first() {
+            Some(target) => target,
+            None => return Ok(Some("ct path is empty".to_string())),
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CtPathValidatorError::Error(e.to_string()))?
+            .as_millis() as u64;
+
+        let mut reasons = vec![];
+        let mut logs = HashSet::new();
+
+        // embedded scts, signed over the precertificate
+        let mut embedded = None;
+        let mut tbs = target.tbs_certificate.clone();
+        if let Some(extensions) = tbs.extensions.as_mut() {
+            if let Some(position) = extensions.iter().position(|e| e.extn_id == ID_CE_SCT_LIST) {
+                embedded = Some(extensions.remove(position));
+            }
+            if extensions.is_empty() {
+                tbs.extensions = None;
+            }
+        }
+        if let Some(extension) = embedded {
+            let precert = tbs.to_der()?;
+            let hashes = self.issuer_key_hashes(path)?;
+            let entries = hashes
+                .iter()
+                .map(|h| Entry::Precert {
+                    issuer_key_hash: h.as_slice(),
+                    tbs: precert.as_slice(),
+                })
+                .collect::<Vec<Entry>>();
+            let list = OctetString::from_der(extension.extn_value.as_bytes())?;
+            match Sct::parse_list(list.as_bytes()) {
+                Ok(scts) => {
+                    for sct in scts.iter() {
+                        match self.verify(sct, target, entries.as_slice(), now) {
+                            Ok(log) => {
+                                logs.insert(log.id.clone());
+                            }
+                            Err(reason) => reasons.push(reason),
+                        }
+                    }
+                }
+                Err(reason) => reasons.push(format!("embedded: {}", reason)),
+            }
+        }
+
+        // supplied scts, signed over the certificate
+        let der = target.to_der()?;
+        let entries = [Entry::X509(der.as_slice())];
+        for list in evidence.scts.iter() {
+            match Sct::parse_list(list.as_slice()) {
+                Ok(scts) => {
+                    for sct in scts.iter() {
+                        match self.verify(sct, target, &entries, now) {
+                            Ok(log) => {
+                                logs.insert(log.id.clone());
+                            }
+                            Err(reason) => reasons.push(reason),
+                        }
+                    }
+                }
+                Err(reason) => reasons.push(format!("supplied: {}", reason)),
+            }
+        }
+
+        if logs.len() >= self.minimum {
+            return Ok(None);
+        }
+
+        let mut reason = format!(
+            "ct policy not met: scts verified from {} of {} required logs",
+            logs.len(),
+            self.minimum
+        );
+        if !reasons.is_empty() {
+            reason = format!("{}: {}", reason, reasons.join("; "));
+        }
+        Ok(Some(reason))
+    }
+}
+
+impl<V> PathValidator for CtPathValidator<V>
+where
+    V: PathValidator,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = CtPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self.validate_with_evidence(path, &Evidence::default())?.0)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let used = match self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .map_err(|e| CtPathValidatorError::PathValidatorError(Box::new(e)))?
+        {
+            (CertificatePathValidation::NotFound(reason), _) => {
+                return Ok((CertificatePathValidation::NotFound(reason), vec![]));
+            }
+            (CertificatePathValidation::Found, used) => used,
+        };
+
+        match self.unqualified(path.as_slice(), evidence)? {
+            Some(reason) => Ok((CertificatePathValidation::NotFound(reason), vec![])),
+            None => Ok((CertificatePathValidation::Found, used)),
+        }
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .map_err(|e| CtPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+// rfc 3339 UTC time, as in log lists. fractional seconds are truncated
+fn rfc3339(time: &str) -> Result<SystemTime, String> {
+    let error = || format!("invalid time {}", time);
+    let time = time.strip_suffix('Z').ok_or_else(error)?;
+    let time = time.split_once('.').map_or(time, |(time, _)| time);
+    let field = |range: std::ops::Range<usize>| {
+        time.get(range)
+            .and_then(|f| f.parse::<u16>().ok())
+            .ok_or_else(error)
+    };
+    let bytes = time.as_bytes();
+    if bytes.len() != 19 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' {
+        return Err(error());
+    }
+    DateTime::new(
+        field(0..4)?,
+        field(5..7)? as u8,
+        field(8..10)? as u8,
+        field(11..13)? as u8,
+        field(14..16)? as u8,
+        field(17..19)? as u8,
+    )
+    .map(|t| t.to_system_time())
+    .map_err(|e| e.to_string())
+}
+
+impl PathValidatorError for CtPathValidatorError {}
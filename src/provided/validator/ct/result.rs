@@ -0,0 +1,70 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type CtPathValidatorResult<T> = result::Result<T, CtPathValidatorError>;
+
+#[derive(Debug)]
+pub enum CtPathValidatorError {
+    Error(String),
+    IoError(std::io::Error),
+    JsonError(serde_json::Error),
+    DerError(der::Error),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for CtPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtPathValidatorError::Error(e) => {
+                write!(f, "ct path validator -> error: {}", e)
+            }
+            CtPathValidatorError::IoError(e) => {
+                write!(f, "ct path validator -> io error: {}", e)
+            }
+            CtPathValidatorError::JsonError(e) => {
+                write!(f, "ct path validator -> json error: {}", e)
+            }
+            CtPathValidatorError::DerError(e) => {
+                write!(f, "ct path validator -> der error: {}", e)
+            }
+            CtPathValidatorError::PathValidatorError(e) => {
+                write!(f, "ct path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for CtPathValidatorError {}
+
+impl From<std::io::Error> for CtPathValidatorError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for CtPathValidatorError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::JsonError(e)
+    }
+}
+
+impl From<der::Error> for CtPathValidatorError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for CtPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<CtPathValidatorError> for X509PathFinderError {
+    fn from(e: CtPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
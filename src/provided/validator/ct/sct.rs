@@ -0,0 +1,116 @@
+// RFC 6962 signed certificate timestamps, TLS encoded
+
+// big-endian tls reader
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < len {
+            return Err("sct truncated".to_string());
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn uint(&mut self, len: usize) -> Result<u64, String> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |n, b| (n << 8) | u64::from(*b)))
+    }
+
+    // opaque vector with a `len` byte length prefix
+    fn vector(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let n = self.uint(len)? as usize;
+        self.take(n)
+    }
+}
+
+pub struct Sct {
+    pub version: u8,
+    pub log_id: Vec<u8>,
+    pub timestamp: u64,
+    pub extensions: Vec<u8>,
+    pub hash_algorithm: u8,
+    pub signature_algorithm: u8,
+    pub signature: Vec<u8>,
+}
+
+// signed entry, rfc 6962 section 3.2
+pub enum Entry<'a> {
+    X509(&'a [u8]),
+    Precert {
+        issuer_key_hash: &'a [u8],
+        tbs: &'a [u8],
+    },
+}
+
+impl Sct {
+    // SignedCertificateTimestampList, rfc 6962 section 3.3
+    pub fn parse_list(list: &[u8]) -> Result<Vec<Self>, String> {
+        let mut reader = Reader::new(list);
+        let mut scts = Reader::new(reader.vector(2)?);
+        if !reader.is_empty() {
+            return Err("trailing bytes after sct list".to_string());
+        }
+
+        let mut parsed = vec![];
+        while !scts.is_empty() {
+            parsed.push(Self::parse(scts.vector(2)?)?);
+        }
+        Ok(parsed)
+    }
+
+    fn parse(sct: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(sct);
+        let parsed = Self {
+            version: reader.uint(1)? as u8,
+            log_id: reader.take(32)?.to_vec(),
+            timestamp: reader.uint(8)?,
+            extensions: reader.vector(2)?.to_vec(),
+            hash_algorithm: reader.uint(1)? as u8,
+            signature_algorithm: reader.uint(1)? as u8,
+            signature: reader.vector(2)?.to_vec(),
+        };
+        if !reader.is_empty() {
+            return Err("trailing bytes after sct".to_string());
+        }
+        Ok(parsed)
+    }
+
+    // digitally-signed struct the log signed
+    pub fn signed_data(&self, entry: &Entry) -> Vec<u8> {
+        let mut data = vec![self.version, 0];
+        data.extend(self.timestamp.to_be_bytes());
+        match entry {
+            Entry::X509(certificate) => {
+                data.extend([0, 0]);
+                data.extend(&(certificate.len() as u32).to_be_bytes()[1..]);
+                data.extend(*certificate);
+            }
+            Entry::Precert {
+                issuer_key_hash,
+                tbs,
+            } => {
+                data.extend([0, 1]);
+                data.extend(*issuer_key_hash);
+                data.extend(&(tbs.len() as u32).to_be_bytes()[1..]);
+                data.extend(*tbs);
+            }
+        }
+        data.extend((self.extensions.len() as u16).to_be_bytes());
+        data.extend(self.extensions.as_slice());
+        data
+    }
+}
@@ -2,20 +2,29 @@
 
 pub mod result;
 
-use crate::api::{CertificatePathValidation, PathValidator, PathValidatorError};
+use crate::api::{
+    CertificatePathValidation, Evidence, PathValidator, PathValidatorError, RevocationEvidence,
+};
 use crate::provided::validator::default::result::DefaultPathValidatorError;
 use der::Encode;
 use std::time::SystemTime;
 use webpki::{CertRevocationList, EndEntityCert, KeyUsage, SignatureAlgorithm, Time, TrustAnchor};
 
-/// Default [`PathValidator`](crate::api::PathValidator)
-pub struct DefaultPathValidator<'a> {
+/// Default [`PathValidator`](crate::api::PathValidator). Validators made with [`new_sync`](Self::new_sync) are `Send + Sync`,
+/// so can be wrapped in a [`DynPathValidator`](crate::provided::validator::dynamic::DynPathValidator), composite or
+/// revocation checking validator. Paths are validated at the current time, or at [`Evidence::time`] when supplied.
+pub struct DefaultPathValidator<'a, C: ?Sized = dyn CertRevocationList + 'a> {
     algorithms: &'a [&'a SignatureAlgorithm],
     roots: Vec<TrustAnchor<'a>>,
     usage: KeyUsage,
-    crls: &'a [&'a dyn CertRevocationList],
+    crls: &'a [&'a C],
 }
 
+/// `Send + Sync` [`DefaultPathValidator`], made with
+/// [`new_sync`](DefaultPathValidator::new_sync)
+pub type SyncDefaultPathValidator<'a> =
+    DefaultPathValidator<'a, dyn CertRevocationList + Sync + 'a>;
+
 impl<'a> DefaultPathValidator<'a> {
     /// Constructor takes arguments from [`verify_for_usage()`](https://docs.rs/rustls-webpki/0.101.5/webpki/struct.EndEntityCert.html#method.verify_for_usage)
     /// * `algorithms` is the list of signature algorithms that are
@@ -41,13 +50,30 @@ impl<'a> DefaultPathValidator<'a> {
     }
 }
 
-impl<'a> PathValidator for DefaultPathValidator<'a> {
-    type PathValidatorError = DefaultPathValidatorError;
+impl<'a> SyncDefaultPathValidator<'a> {
+    /// Constructor for a `Send + Sync` validator, takes the same arguments as [`new`](DefaultPathValidator::new)
+    pub fn new_sync(
+        algorithms: &'a [&'a SignatureAlgorithm],
+        roots: Vec<TrustAnchor<'a>>,
+        usage: KeyUsage,
+        crls: &'a [&'a (dyn CertRevocationList + Sync)],
+    ) -> Self {
+        Self {
+            algorithms,
+            roots,
+            usage,
+            crls,
+        }
+    }
+}
 
-    fn validate(
+impl<'a, C: ?Sized> DefaultPathValidator<'a, C> {
+    fn verify(
         &self,
         path: Vec<&crate::Certificate>,
-    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        crls: &[&dyn CertRevocationList],
+        time: SystemTime,
+    ) -> Result<CertificatePathValidation, DefaultPathValidatorError> {
         if path.is_empty() {
             return Ok(CertificatePathValidation::NotFound(
                 "path is empty".to_string(),
@@ -70,10 +96,9 @@ impl<'a> PathValidator for DefaultPathValidator<'a> {
                 .map(Vec::as_slice)
                 .collect::<Vec<&[u8]>>()
                 .as_slice(),
-            Time::try_from(SystemTime::now())
-                .map_err(|e| DefaultPathValidatorError::Error(e.to_string()))?,
+            Time::try_from(time).map_err(|e| DefaultPathValidatorError::Error(e.to_string()))?,
             self.usage,
-            self.crls,
+            crls,
         ) {
             Ok(_) => Ok(CertificatePathValidation::Found),
 
@@ -81,4 +106,60 @@ impl<'a> PathValidator for DefaultPathValidator<'a> {
         }
     }
 }
+
+impl<'a> PathValidator for DefaultPathValidator<'a> {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.verify(path, self.crls, SystemTime::now())
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let time = evidence.time.unwrap_or_else(SystemTime::now);
+        Ok((self.verify(path, self.crls, time)?, vec![]))
+    }
+}
+
+impl<'a> SyncDefaultPathValidator<'a> {
+    // webpki takes crls without the `Sync` bound
+    fn sync_crls(&self) -> Vec<&dyn CertRevocationList> {
+        self.crls
+            .iter()
+            .map(|crl| *crl as &dyn CertRevocationList)
+            .collect()
+    }
+}
+
+impl<'a> PathValidator for SyncDefaultPathValidator<'a> {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.verify(path, self.sync_crls().as_slice(), SystemTime::now())
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let time = evidence.time.unwrap_or_else(SystemTime::now);
+        Ok((
+            self.verify(path, self.sync_crls().as_slice(), time)?,
+            vec![],
+        ))
+    }
+}
+
 impl PathValidatorError for DefaultPathValidatorError {}
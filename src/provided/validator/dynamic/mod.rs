@@ -0,0 +1,218 @@
+//! Type-erased [`PathValidator`] and [`AsyncPathValidator`],
+//! for validators selected at runtime
+
+pub mod result;
+
+use crate::api::{
+    AsyncPathValidator, CertificatePathValidation, Evidence, PathValidator, PathValidatorError,
+    RevocationEvidence,
+};
+use crate::provided::validator::dynamic::result::DynPathValidatorError;
+use crate::report::CacheCounters;
+use async_trait::async_trait;
+
+// wraps validator, erasing its error type. Async validators are wrapped as `Erased<Async<V>>`, so the
+// sync and async wrappers share it without overlapping the blanket `AsyncPathValidator` impl
+struct Erased<V>(V);
+
+// async validator to erase
+struct Async<V>(V);
+
+impl<V> Erased<Async<V>> {
+    fn validator(&self) -> &V {
+        let Erased(Async(validator)) = self;
+        validator
+    }
+}
+
+fn erase<E>(e: E) -> DynPathValidatorError
+where
+    E: PathValidatorError + Send + Sync + 'static,
+{
+    DynPathValidatorError::PathValidatorError(Box::new(e))
+}
+
+impl<V> PathValidator for Erased<V>
+where
+    V: PathValidator,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = DynPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.0.validate(path).map_err(erase)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        self.0.validate_with_evidence(path, evidence).map_err(erase)
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.0.check_link(child, issuer).map_err(erase)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.0.cache_counters()
+    }
+}
+
+#[async_trait]
+impl<V> AsyncPathValidator for Erased<Async<V>>
+where
+    V: AsyncPathValidator + Sync,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = DynPathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator().validate(path).await.map_err(erase)
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        self.validator()
+            .validate_with_evidence(path, evidence)
+            .await
+            .map_err(erase)
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator()
+            .check_link(child, issuer)
+            .await
+            .map_err(erase)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator().cache_counters()
+    }
+}
+
+/// Boxed [`PathValidator`] with its error type erased.
+/// Any validator, whatever its error type, can be wrapped and used with [`X509PathFinder`](crate::X509PathFinder).
+pub struct DynPathValidator<'v> {
+    inner: Box<dyn PathValidator<PathValidatorError = DynPathValidatorError> + Send + Sync + 'v>,
+}
+
+impl<'v> DynPathValidator<'v> {
+    /// Wrap `validator`
+    pub fn new<V>(validator: V) -> Self
+    where
+        V: PathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        Self {
+            inner: Box::new(Erased(validator)),
+        }
+    }
+}
+
+impl<'v> PathValidator for DynPathValidator<'v> {
+    type PathValidatorError = DynPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        self.inner.validate_with_evidence(path, evidence)
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer)
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+/// Boxed [`AsyncPathValidator`] with its error type erased. Any validator, sync or async,
+/// can be wrapped, such as a revocation checking validator selected at runtime.
+pub struct DynAsyncPathValidator<'v> {
+    inner:
+        Box<dyn AsyncPathValidator<PathValidatorError = DynPathValidatorError> + Send + Sync + 'v>,
+}
+
+impl<'v> DynAsyncPathValidator<'v> {
+    /// Wrap `validator`
+    pub fn new<V>(validator: V) -> Self
+    where
+        V: AsyncPathValidator + Send + Sync + 'v,
+        V::PathValidatorError: Send + Sync + 'static,
+    {
+        Self {
+            inner: Box::new(Erased(Async(validator))),
+        }
+    }
+}
+
+#[async_trait]
+impl<'v> AsyncPathValidator for DynAsyncPathValidator<'v> {
+    type PathValidatorError = DynPathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.validate(path).await
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        self.inner.validate_with_evidence(path, evidence).await
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.inner.check_link(child, issuer).await
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.inner.cache_counters()
+    }
+}
+
+impl PathValidatorError for DynPathValidatorError {}
@@ -0,0 +1,38 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type DynPathValidatorResult<T> = result::Result<T, DynPathValidatorError>;
+
+#[derive(Debug)]
+pub enum DynPathValidatorError {
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for DynPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DynPathValidatorError::PathValidatorError(e) => {
+                write!(f, "{}", e)
+            }
+        }
+    }
+}
+
+impl Error for DynPathValidatorError {}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for DynPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<DynPathValidatorError> for X509PathFinderError {
+    fn from(e: DynPathValidatorError) -> Self {
+        match e {
+            DynPathValidatorError::PathValidatorError(e) => Self::PathValidatorError(e),
+        }
+    }
+}
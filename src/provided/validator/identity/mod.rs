@@ -0,0 +1,253 @@
+//! Server identity verifying [`PathValidator`] implementation
+
+pub mod result;
+
+use crate::api::{
+    CertificatePathValidation, Evidence, PathValidator, PathValidatorError, RevocationEvidence,
+};
+use crate::provided::validator::identity::result::{
+    IdentityPathValidatorError, IdentityPathValidatorResult,
+};
+use crate::report::CacheCounters;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::SubjectAltName;
+
+/// Expected server identity
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ServerIdentity {
+    /// DNS name, lowercase and without a trailing dot
+    Dns(String),
+    /// IP address
+    Ip(IpAddr),
+}
+
+impl ServerIdentity {
+    /// Parses `name` as an IP address, or otherwise as a DNS name
+    pub fn parse(name: &str) -> IdentityPathValidatorResult<Self> {
+        if let Ok(ip) = name.parse::<IpAddr>() {
+            return Ok(Self::Ip(ip));
+        }
+
+        let dns = name.strip_suffix('.').unwrap_or(name).to_ascii_lowercase();
+        let valid = !dns.is_empty()
+            && dns.len() <= 253
+            && dns.split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            });
+        if !valid {
+            return Err(IdentityPathValidatorError::Error(format!(
+                "invalid server identity {}",
+                name
+            )));
+        }
+
+        Ok(Self::Dns(dns))
+    }
+}
+
+impl Display for ServerIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerIdentity::Dns(dns) => write!(f, "{}", dns),
+            ServerIdentity::Ip(ip) => write!(f, "{}", ip),
+        }
+    }
+}
+
+/// Certificate doesn't present the expected identity. Its [`Display`] is the
+/// [`NotFound`](crate::api::CertificatePathValidation::NotFound) reason of an [`IdentityPathValidator`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IdentityMismatch {
+    /// Expected identity
+    pub expected: ServerIdentity,
+    /// Subject of the certificate
+    pub subject: String,
+    /// DNS names and IP addresses in the certificate's subject alternative names
+    pub presented: Vec<String>,
+}
+
+impl Display for IdentityMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "identity mismatch: expected {}, {} presents [{}]",
+            self.expected,
+            self.subject,
+            self.presented.join(", ")
+        )
+    }
+}
+
+/// Verifies `certificate` presents `identity` in its subject alternative names.
+///
+/// DNS names match case-insensitively. A wildcard is only honored as the whole left-most label of a name with at least
+/// two further labels, and matches exactly one label. IP addresses only match IP address entries. The subject common
+/// name is not consulted.
+pub fn verify_identity(
+    certificate: &crate::Certificate,
+    identity: &ServerIdentity,
+) -> Result<(), IdentityMismatch> {
+    let names = certificate
+        .tbs_certificate
+        .get::<SubjectAltName>()
+        .ok()
+        .flatten()
+        .map_or_else(Vec::new, |(_, san)| san.0);
+
+    let mut presented = vec![];
+    for name in names {
+        match name {
+            GeneralName::DnsName(dns) => {
+                if let ServerIdentity::Dns(expected) = identity {
+                    if dns_matches(dns.as_str(), expected) {
+                        return Ok(());
+                    }
+                }
+                presented.push(dns.to_string());
+            }
+            GeneralName::IpAddress(octets) => {
+                let ip = match octets.as_bytes().len() {
+                    4 => <[u8; 4]>::try_from(octets.as_bytes())
+                        .ok()
+                        .map(IpAddr::from),
+                    16 => <[u8; 16]>::try_from(octets.as_bytes())
+                        .ok()
+                        .map(IpAddr::from),
+                    _ => None,
+                };
+                if let Some(ip) = ip {
+                    if *identity == ServerIdentity::Ip(ip) {
+                        return Ok(());
+                    }
+                    presented.push(ip.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(IdentityMismatch {
+        expected: identity.clone(),
+        subject: certificate.tbs_certificate.subject.to_string(),
+        presented,
+    })
+}
+
+// rfc 6125 section 6.4, wildcard only as the complete left-most label
+fn dns_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern
+        .strip_suffix('.')
+        .unwrap_or(pattern)
+        .to_ascii_lowercase();
+
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => {
+            !suffix.contains('*')
+                && suffix.split('.').count() >= 2
+                && name
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix)
+        }
+        None => !pattern.contains('*') && pattern == name,
+    }
+}
+
+/// Requires the target of paths found valid by the wrapped validator to present an expected
+/// [`ServerIdentity`]. See [`verify_identity`] for matching rules. Mismatched paths are rejected with an
+/// [`IdentityMismatch`] reason.
+pub struct IdentityPathValidator<V>
+where
+    V: PathValidator,
+{
+    validator: V,
+    identity: ServerIdentity,
+}
+
+impl<V> IdentityPathValidator<V>
+where
+    V: PathValidator,
+{
+    /// Constructor takes the validator to wrap, and the expected `identity` of the target
+    pub fn new(validator: V, identity: ServerIdentity) -> Self {
+        Self {
+            validator,
+            identity,
+        }
+    }
+
+    /// Expected identity
+    pub fn identity(&self) -> &ServerIdentity {
+        &self.identity
+    }
+}
+
+impl<V> PathValidator for IdentityPathValidator<V>
+where
+    V: PathValidator,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = IdentityPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self.validate_with_evidence(path, &Evidence::default())?.0)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let validated = self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .map_err(|e| IdentityPathValidatorError::PathValidatorError(Box::new(e)))?;
+
+        if let (CertificatePathValidation::Found, _) = &validated {
+            let target = match path.first() {
+                Some(target) => target,
+                None => {
+                    return Ok((
+                        CertificatePathValidation::NotFound("path is empty".to_string()),
+                        vec![],
+                    ))
+                }
+            };
+            if let Err(mismatch) = verify_identity(target, &self.identity) {
+                return Ok((
+                    CertificatePathValidation::NotFound(mismatch.to_string()),
+                    vec![],
+                ));
+            }
+        }
+
+        Ok(validated)
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .map_err(|e| IdentityPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+impl PathValidatorError for IdentityPathValidatorError {}
@@ -0,0 +1,40 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type IdentityPathValidatorResult<T> = result::Result<T, IdentityPathValidatorError>;
+
+#[derive(Debug)]
+pub enum IdentityPathValidatorError {
+    Error(String),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for IdentityPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentityPathValidatorError::Error(e) => {
+                write!(f, "identity path validator -> error: {}", e)
+            }
+            IdentityPathValidatorError::PathValidatorError(e) => {
+                write!(f, "identity path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for IdentityPathValidatorError {}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for IdentityPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<IdentityPathValidatorError> for X509PathFinderError {
+    fn from(e: IdentityPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
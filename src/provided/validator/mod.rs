@@ -3,4 +3,18 @@
 #[cfg(feature = "openssl")]
 pub mod openssl;
 
+pub mod cache;
+pub mod composite;
+#[cfg(feature = "rustls-webpki")]
+pub mod crl;
+#[cfg(feature = "rustls-webpki")]
+pub mod ct;
 pub mod default;
+pub mod dynamic;
+pub mod identity;
+#[cfg(feature = "rustls-webpki")]
+pub mod ocsp;
+pub mod pin;
+pub mod policy;
+#[cfg(feature = "rustls-webpki")]
+mod revocation;
@@ -0,0 +1,526 @@
+//! OCSP revocation checking [`AsyncPathValidator`] implementation
+
+pub mod result;
+
+use crate::anchor::TrustAnchor;
+use crate::api::{
+    AsyncPathValidator, CertificatePathValidation, Evidence, Fetcher, PathValidatorError,
+    RevocationEvidence,
+};
+use crate::certificate::Certificate;
+use crate::provided::validator::ocsp::result::{OcspPathValidatorError, OcspPathValidatorResult};
+use crate::provided::validator::revocation::{self, Checked};
+use crate::report::CacheCounters;
+use crate::signature;
+use async_trait::async_trait;
+use der::asn1::{Null, OctetString};
+use der::oid::db::rfc5280::ID_KP_OCSP_SIGNING;
+use der::oid::db::rfc5912::ID_SHA_1;
+use der::oid::db::rfc6960::ID_PKIX_OCSP_BASIC;
+use der::{Decode, Encode};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+use url::Url;
+use webpki::SignatureAlgorithm;
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_cert::ext::pkix::ExtendedKeyUsage;
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_ocsp::{
+    BasicOcspResponse, CertId, CertStatus, OcspRequest, OcspResponse, OcspResponseStatus, Request,
+    ResponderId, TbsRequest, Version,
+};
+
+/// Reason an [`OcspPathValidator`] rejected a path. Its [`Display`] is the
+/// [`NotFound`](crate::api::CertificatePathValidation::NotFound) reason.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OcspFailure {
+    /// Responder reported the certificate revoked
+    Revoked {
+        /// Subject of the revoked certificate
+        subject: String,
+        /// Time of revocation
+        revocation_time: SystemTime,
+        /// Reason for revocation, if given
+        reason: Option<CrlReason>,
+    },
+    /// Responder doesn't know the certificate
+    Unknown {
+        /// Subject of the unknown certificate
+        subject: String,
+    },
+    /// No response could be fetched and verified
+    Unavailable {
+        /// Subject of the unchecked certificate
+        subject: String,
+        /// Reason each responder failed
+        reasons: Vec<String>,
+    },
+}
+
+impl Display for OcspFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcspFailure::Revoked {
+                subject,
+                reason: Some(reason),
+                ..
+            } => write!(f, "ocsp certificate revoked: {}: {:?}", subject, reason),
+            OcspFailure::Revoked { subject, .. } => {
+                write!(f, "ocsp certificate revoked: {}", subject)
+            }
+            OcspFailure::Unknown { subject } => {
+                write!(f, "ocsp certificate unknown: {}", subject)
+            }
+            OcspFailure::Unavailable { subject, reasons } => write!(
+                f,
+                "ocsp revocation status unavailable: {}: {}",
+                subject,
+                reasons.join("; ")
+            ),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Status {
+    cert_status: CertStatus,
+    next_update: Option<SystemTime>,
+    der: Vec<u8>,
+}
+
+/// Checks revocation of paths found valid by the wrapped validator, with
+/// [OCSP](https://datatracker.ietf.org/doc/html/rfc6960) responders named in each certificate's
+/// Authority Information Access `id-ad-ocsp` locations.
+///
+/// Responses supplied as [`RevocationEvidence`], such as stapled responses, are used
+/// before requesting any. Otherwise, requests are posted with a [`Fetcher`]. A response is only used once its signature is
+/// verified, either by the issuing certificate or by a delegated responder certificate issued by it for OCSP signing,
+/// and while it's fresh: before its `nextUpdate`, or within a maximum age of its `thisUpdate` when it has none.
+/// Responses with a `nextUpdate` are cached until then. Self-signed certificates and certificates
+/// without OCSP locations are not checked. A path is rejected with an [`OcspFailure`] when a certificate is revoked
+/// or unknown, or when none of its responders give a verified response.
+pub struct OcspPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator,
+    F: Fetcher,
+{
+    validator: V,
+    fetcher: F,
+    algorithms: &'a [&'a SignatureAlgorithm],
+    anchors: Vec<TrustAnchor>,
+    max_age: Duration,
+    cache: Mutex<HashMap<Vec<u8>, Status>>,
+}
+
+impl<'a, V, F> OcspPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator,
+    F: Fetcher,
+{
+    /// Constructor takes the validator to wrap, and:
+    /// * `fetcher` to post OCSP requests with
+    /// * `algorithms` is the list of signature algorithms trusted for response and responder certificate signatures
+    /// * `anchors` are trust anchors, used to verify responses for the top certificate in a path
+    /// * `max_age` is the maximum age, from `thisUpdate`, of responses without a `nextUpdate`
+    pub fn new(
+        validator: V,
+        fetcher: F,
+        algorithms: &'a [&'a SignatureAlgorithm],
+        anchors: Vec<TrustAnchor>,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            validator,
+            fetcher,
+            algorithms,
+            anchors,
+            max_age,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Vec<u8>, Status>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // public key that signed the response, if the issuer or a responder it delegated to
+    fn signer(
+        &self,
+        response: &BasicOcspResponse,
+        certificate: &crate::Certificate,
+        issuer_key: &SubjectPublicKeyInfoOwned,
+        now: SystemTime,
+    ) -> Result<SubjectPublicKeyInfoOwned, String> {
+        let responder = &response.tbs_response_data.responder_id;
+        if responder_matches(responder, &certificate.tbs_certificate.issuer, issuer_key) {
+            return Ok(issuer_key.clone());
+        }
+
+        for delegate in response.certs.iter().flatten() {
+            let tbs = &delegate.tbs_certificate;
+            if !responder_matches(responder, &tbs.subject, &tbs.subject_public_key_info) {
+                continue;
+            }
+            if tbs.issuer != certificate.tbs_certificate.issuer {
+                return Err(format!("responder issued by {}", tbs.issuer));
+            }
+            signature::verify_certificate(self.algorithms, issuer_key, delegate)
+                .map_err(|e| format!("responder certificate {}", e))?;
+            let signing = tbs
+                .get::<ExtendedKeyUsage>()
+                .ok()
+                .flatten()
+                .is_some_and(|(_, eku)| eku.0.contains(&ID_KP_OCSP_SIGNING));
+            if !signing {
+                return Err("responder not authorized for ocsp signing".to_string());
+            }
+            if tbs.validity.not_before.to_system_time() > now
+                || tbs.validity.not_after.to_system_time() < now
+            {
+                return Err("responder certificate expired".to_string());
+            }
+            return Ok(tbs.subject_public_key_info.clone());
+        }
+
+        Err("responder not found".to_string())
+    }
+
+    // status of certificate in a response, once verified and if fresh
+    fn single(
+        &self,
+        basic: &BasicOcspResponse,
+        cert_id: &CertId,
+        certificate: &crate::Certificate,
+        issuer_key: &SubjectPublicKeyInfoOwned,
+        now: SystemTime,
+    ) -> Result<(CertStatus, Option<SystemTime>), String> {
+        let signer = self.signer(basic, certificate, issuer_key, now)?;
+        let tbs = basic
+            .tbs_response_data
+            .to_der()
+            .map_err(|e| e.to_string())?;
+        let signature_bytes = basic
+            .signature
+            .as_bytes()
+            .ok_or_else(|| "malformed signature".to_string())?;
+        signature::verify(
+            self.algorithms,
+            &signer,
+            &basic.signature_algorithm.oid,
+            tbs.as_slice(),
+            signature_bytes,
+        )
+        .map_err(|e| format!("response {}", e))?;
+
+        let single = basic
+            .tbs_response_data
+            .responses
+            .iter()
+            .find(|r| same_cert_id(&r.cert_id, cert_id))
+            .ok_or_else(|| "certificate not in response".to_string())?;
+        let this_update = single.this_update.0.to_system_time();
+        if this_update > now {
+            return Err("response not yet valid".to_string());
+        }
+        let next_update = single.next_update.map(|n| n.0.to_system_time());
+        if next_update.unwrap_or(this_update + self.max_age) <= now {
+            return Err("response expired".to_string());
+        }
+
+        Ok((single.cert_status, next_update))
+    }
+
+    // cached status if fresh, otherwise requested. only verified statuses are cached
+    async fn status(
+        &self,
+        url: &Url,
+        certificate: &crate::Certificate,
+        issuer_key: &SubjectPublicKeyInfoOwned,
+        cert_id: &CertId,
+    ) -> Result<Status, String> {
+        let now = SystemTime::now();
+
+        let key = cert_id.to_der().map_err(|e| e.to_string())?;
+        let cached = self.lock().get(&key).cloned();
+        if let Some(status) = cached {
+            if status.next_update.is_some_and(|n| n > now) {
+                return Ok(status);
+            }
+        }
+
+        let request = OcspRequest {
+            tbs_request: TbsRequest {
+                version: Version::V1,
+                requestor_name: None,
+                request_list: vec![Request {
+                    req_cert: cert_id.clone(),
+                    single_request_extensions: None,
+                }],
+                request_extensions: None,
+            },
+            optional_signature: None,
+        }
+        .to_der()
+        .map_err(|e| e.to_string())?;
+
+        let der = self
+            .fetcher
+            .post(url, "application/ocsp-request", request)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let basic = basic(der.as_slice())?;
+        let (cert_status, next_update) =
+            self.single(&basic, cert_id, certificate, issuer_key, now)?;
+        let status = Status {
+            cert_status,
+            next_update,
+            der,
+        };
+        if next_update.is_some() {
+            self.lock().insert(key, status.clone());
+        }
+
+        Ok(status)
+    }
+
+    // reason path is rejected, if any. responses proving certificates not revoked are appended to `used`
+    async fn revoked(
+        &self,
+        path: &[&crate::Certificate],
+        evidence: &Evidence,
+        used: &mut Vec<RevocationEvidence>,
+    ) -> OcspPathValidatorResult<Option<OcspFailure>> {
+        let supplied = evidence
+            .revocation
+            .iter()
+            .filter_map(|e| match e {
+                RevocationEvidence::Ocsp(der) => basic(der.as_slice()).ok().map(|b| (der, b)),
+                _ => None,
+            })
+            .collect::<Vec<(&Vec<u8>, BasicOcspResponse)>>();
+        let supplied = supplied.as_slice();
+
+        revocation::check_path(
+            path,
+            self.anchors.as_slice(),
+            used,
+            move |certificate, _, keys| self.check(certificate, keys, supplied),
+        )
+        .await
+    }
+
+    // revocation status of certificate, from supplied responses before requesting any
+    async fn check(
+        &self,
+        certificate: &crate::Certificate,
+        keys: Vec<SubjectPublicKeyInfoOwned>,
+        supplied: &[(&Vec<u8>, BasicOcspResponse)],
+    ) -> OcspPathValidatorResult<Checked<OcspFailure>> {
+        if certificate.tbs_certificate.issuer == certificate.tbs_certificate.subject {
+            return Ok(Checked::Skipped);
+        }
+
+        let now = SystemTime::now();
+        let urls = Certificate::parse_ocsp(certificate);
+        let mut ids = vec![];
+        for key in keys.iter() {
+            ids.push((key, cert_id(certificate, key)?));
+        }
+        let mut stapled = vec![];
+        for (key, id) in ids.iter() {
+            for (der, basic) in supplied.iter() {
+                if basic
+                    .tbs_response_data
+                    .responses
+                    .iter()
+                    .any(|r| same_cert_id(&r.cert_id, id))
+                {
+                    stapled.push((*key, id, *der, basic));
+                }
+            }
+        }
+        if urls.is_empty() && stapled.is_empty() {
+            return Ok(Checked::Skipped);
+        }
+
+        let subject = certificate.tbs_certificate.subject.to_string();
+        if keys.is_empty() {
+            return Ok(Checked::Rejected(OcspFailure::Unavailable {
+                subject,
+                reasons: vec!["issuer not found".to_string()],
+            }));
+        }
+
+        let mut reasons = vec![];
+
+        for (key, id, der, basic) in stapled {
+            match self.single(basic, id, certificate, key, now) {
+                Ok((cert_status, _)) => {
+                    return Ok(match failure(&cert_status, subject.as_str()) {
+                        Some(failure) => Checked::Rejected(failure),
+                        None => Checked::Good(RevocationEvidence::Ocsp(der.clone())),
+                    })
+                }
+                Err(reason) => reasons.push(format!("supplied response: {}", reason)),
+            }
+        }
+
+        for url in urls.iter() {
+            for (key, id) in ids.iter() {
+                match self.status(url, certificate, key, id).await {
+                    Ok(status) => {
+                        return Ok(match failure(&status.cert_status, subject.as_str()) {
+                            Some(failure) => Checked::Rejected(failure),
+                            None => Checked::Good(RevocationEvidence::Ocsp(status.der)),
+                        })
+                    }
+                    Err(reason) => reasons.push(format!("{}: {}", url, reason)),
+                }
+            }
+        }
+
+        Ok(Checked::Rejected(OcspFailure::Unavailable {
+            subject,
+            reasons,
+        }))
+    }
+}
+
+#[async_trait]
+impl<'a, V, F> AsyncPathValidator for OcspPathValidator<'a, V, F>
+where
+    V: AsyncPathValidator + Sync,
+    V::PathValidatorError: Send + Sync + 'static,
+    F: Fetcher,
+{
+    type PathValidatorError = OcspPathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self
+            .validate_with_evidence(path, &Evidence::default())
+            .await?
+            .0)
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let mut used = match self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .await
+            .map_err(|e| OcspPathValidatorError::PathValidatorError(Box::new(e)))?
+        {
+            (CertificatePathValidation::NotFound(reason), _) => {
+                return Ok((CertificatePathValidation::NotFound(reason), vec![]));
+            }
+            (CertificatePathValidation::Found, used) => used,
+        };
+
+        match self.revoked(path.as_slice(), evidence, &mut used).await? {
+            Some(failure) => Ok((
+                CertificatePathValidation::NotFound(failure.to_string()),
+                vec![],
+            )),
+            None => Ok((CertificatePathValidation::Found, used)),
+        }
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .await
+            .map_err(|e| OcspPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+// successful basic response
+fn basic(der: &[u8]) -> Result<BasicOcspResponse, String> {
+    let response = OcspResponse::from_der(der).map_err(|e| e.to_string())?;
+    if response.response_status != OcspResponseStatus::Successful {
+        return Err(format!("responder status {:?}", response.response_status));
+    }
+    let bytes = response
+        .response_bytes
+        .ok_or_else(|| "response has no bytes".to_string())?;
+    if bytes.response_type != ID_PKIX_OCSP_BASIC {
+        return Err(format!("unsupported response type {}", bytes.response_type));
+    }
+    BasicOcspResponse::from_der(bytes.response.as_bytes()).map_err(|e| e.to_string())
+}
+
+// failure for a status other than good
+fn failure(cert_status: &CertStatus, subject: &str) -> Option<OcspFailure> {
+    match cert_status {
+        CertStatus::Good(_) => None,
+        CertStatus::Revoked(info) => Some(OcspFailure::Revoked {
+            subject: subject.to_string(),
+            revocation_time: info.revocation_time.0.to_system_time(),
+            reason: info.revocation_reason,
+        }),
+        CertStatus::Unknown(_) => Some(OcspFailure::Unknown {
+            subject: subject.to_string(),
+        }),
+    }
+}
+
+// sha-1 hashes of issuer name and key, per rfc 6960
+fn cert_id(
+    certificate: &crate::Certificate,
+    issuer_key: &SubjectPublicKeyInfoOwned,
+) -> der::Result<CertId> {
+    Ok(CertId {
+        hash_algorithm: AlgorithmIdentifierOwned {
+            oid: ID_SHA_1,
+            parameters: Some(Null.into()),
+        },
+        issuer_name_hash: OctetString::new(
+            Sha1::digest(certificate.tbs_certificate.issuer.to_der()?).to_vec(),
+        )?,
+        issuer_key_hash: OctetString::new(key_hash(issuer_key))?,
+        serial_number: certificate.tbs_certificate.serial_number.clone(),
+    })
+}
+
+fn key_hash(key: &SubjectPublicKeyInfoOwned) -> Vec<u8> {
+    Sha1::digest(key.subject_public_key.raw_bytes()).to_vec()
+}
+
+// responders may omit or vary hash algorithm parameters
+fn same_cert_id(a: &CertId, b: &CertId) -> bool {
+    a.hash_algorithm.oid == b.hash_algorithm.oid
+        && a.issuer_name_hash == b.issuer_name_hash
+        && a.issuer_key_hash == b.issuer_key_hash
+        && a.serial_number == b.serial_number
+}
+
+fn responder_matches(
+    responder: &ResponderId,
+    name: &x509_cert::name::Name,
+    key: &SubjectPublicKeyInfoOwned,
+) -> bool {
+    match responder {
+        ResponderId::ByName(n) => n == name,
+        ResponderId::ByKey(hash) => hash.as_bytes() == key_hash(key).as_slice(),
+    }
+}
+
+impl PathValidatorError for OcspPathValidatorError {}
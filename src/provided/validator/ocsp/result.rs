@@ -0,0 +1,46 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type OcspPathValidatorResult<T> = result::Result<T, OcspPathValidatorError>;
+
+#[derive(Debug)]
+pub enum OcspPathValidatorError {
+    DerError(der::Error),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for OcspPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcspPathValidatorError::DerError(e) => {
+                write!(f, "ocsp path validator -> der error: {}", e)
+            }
+            OcspPathValidatorError::PathValidatorError(e) => {
+                write!(f, "ocsp path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for OcspPathValidatorError {}
+
+impl From<der::Error> for OcspPathValidatorError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for OcspPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<OcspPathValidatorError> for X509PathFinderError {
+    fn from(e: OcspPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
@@ -0,0 +1,139 @@
+//! SPKI pinning [`PathValidator`] implementation
+
+pub mod result;
+
+use crate::anchor::TrustAnchor;
+use crate::api::{
+    CertificatePathValidation, Evidence, PathValidator, PathValidatorError, RevocationEvidence,
+};
+use crate::provided::validator::pin::result::{PinPathValidatorError, PinPathValidatorResult};
+use crate::report::CacheCounters;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use der::Encode;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// [`NotFound`](crate::api::CertificatePathValidation::NotFound) reason of paths found valid by the wrapped validator,
+/// but matching no pin
+pub const UNPINNED: &str = "path not pinned";
+
+/// Requires paths found valid by the wrapped validator to contain a pinned public key.
+///
+/// Pins are base64 encoded SHA-256 hashes of DER encoded SubjectPublicKeyInfo, as in
+/// [HPKP](https://datatracker.ietf.org/doc/html/rfc7469#section-2.4). A path is pinned when any certificate in it,
+/// or the trust anchor that issued its top certificate, has a pinned key. Otherwise it's rejected with [`UNPINNED`],
+/// and the search continues for a pinned path.
+pub struct PinPathValidator<V>
+where
+    V: PathValidator,
+{
+    validator: V,
+    pins: HashSet<Vec<u8>>,
+    anchors: Vec<TrustAnchor>,
+}
+
+impl<V> PinPathValidator<V>
+where
+    V: PathValidator,
+{
+    /// Constructor takes the validator to wrap, and:
+    /// * `pins` are base64 encoded SHA-256 SPKI hashes
+    /// * `anchors` are trust anchors, whose keys can also be pinned
+    pub fn new<S: AsRef<str>>(
+        validator: V,
+        pins: &[S],
+        anchors: Vec<TrustAnchor>,
+    ) -> PinPathValidatorResult<Self> {
+        let mut decoded = HashSet::new();
+        for pin in pins {
+            let hash = STANDARD.decode(pin.as_ref()).map_err(|e| {
+                PinPathValidatorError::Error(format!("invalid pin {}: {}", pin.as_ref(), e))
+            })?;
+            if hash.len() != 32 {
+                return Err(PinPathValidatorError::Error(format!(
+                    "invalid pin {}: not a sha-256 hash",
+                    pin.as_ref()
+                )));
+            }
+            decoded.insert(hash);
+        }
+
+        Ok(Self {
+            validator,
+            pins: decoded,
+            anchors,
+        })
+    }
+
+    fn pinned(&self, path: &[&crate::Certificate]) -> PinPathValidatorResult<bool> {
+        for certificate in path {
+            let spki = certificate
+                .tbs_certificate
+                .subject_public_key_info
+                .to_der()?;
+            if self.pins.contains(Sha256::digest(spki).as_slice()) {
+                return Ok(true);
+            }
+        }
+
+        if let Some(top) = path.last() {
+            return Ok(self.anchors.iter().any(|a| {
+                a.issued_x509(top) && self.pins.contains(Sha256::digest(a.spki()).as_slice())
+            }));
+        }
+
+        Ok(false)
+    }
+}
+
+impl<V> PathValidator for PinPathValidator<V>
+where
+    V: PathValidator,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = PinPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self.validate_with_evidence(path, &Evidence::default())?.0)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let validated = self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .map_err(|e| PinPathValidatorError::PathValidatorError(Box::new(e)))?;
+
+        match validated {
+            (CertificatePathValidation::Found, _) if !self.pinned(path.as_slice())? => Ok((
+                CertificatePathValidation::NotFound(UNPINNED.to_string()),
+                vec![],
+            )),
+            validated => Ok(validated),
+        }
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .map_err(|e| PinPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+impl PathValidatorError for PinPathValidatorError {}
@@ -0,0 +1,50 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type PinPathValidatorResult<T> = result::Result<T, PinPathValidatorError>;
+
+#[derive(Debug)]
+pub enum PinPathValidatorError {
+    Error(String),
+    DerError(der::Error),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for PinPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinPathValidatorError::Error(e) => {
+                write!(f, "pin path validator -> error: {}", e)
+            }
+            PinPathValidatorError::DerError(e) => {
+                write!(f, "pin path validator -> der error: {}", e)
+            }
+            PinPathValidatorError::PathValidatorError(e) => {
+                write!(f, "pin path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for PinPathValidatorError {}
+
+impl From<der::Error> for PinPathValidatorError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for PinPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<PinPathValidatorError> for X509PathFinderError {
+    fn from(e: PinPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
@@ -0,0 +1,230 @@
+//! Extended key usage and certificate policy [`PathValidator`] implementation
+
+pub mod result;
+
+use crate::api::{
+    CertificatePathValidation, Evidence, PathValidator, PathValidatorError, RevocationEvidence,
+};
+use crate::provided::validator::policy::result::PolicyPathValidatorError;
+use crate::report::CacheCounters;
+use der::oid::db::rfc5280::{
+    ANY_EXTENDED_KEY_USAGE, ANY_POLICY, ID_CE_CERTIFICATE_POLICIES, ID_CE_EXT_KEY_USAGE,
+};
+use der::oid::ObjectIdentifier;
+use std::fmt::{Display, Formatter};
+use x509_cert::ext::pkix::{CertificatePolicies, ExtendedKeyUsage};
+
+/// Reason a [`PolicyPathValidator`] rejected a path. Its [`Display`] is the
+/// [`NotFound`](crate::api::CertificatePathValidation::NotFound) reason.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PolicyFailure {
+    /// Certificate doesn't permit a required extended key usage
+    ExtendedKeyUsage {
+        /// Subject of the certificate
+        subject: String,
+        /// Required extended key usage
+        usage: ObjectIdentifier,
+    },
+    /// Certificate doesn't assert a required certificate policy
+    Policy {
+        /// Subject of the certificate
+        subject: String,
+        /// Required certificate policy
+        policy: ObjectIdentifier,
+    },
+    /// Certificate extension required to check the path can't be decoded
+    Malformed {
+        /// Subject of the certificate
+        subject: String,
+        /// OID of the malformed extension
+        extension: ObjectIdentifier,
+        /// Decoding error
+        reason: String,
+    },
+}
+
+impl Display for PolicyFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyFailure::ExtendedKeyUsage { subject, usage } => write!(
+                f,
+                "extended key usage not permitted: {}: {}",
+                subject, usage
+            ),
+            PolicyFailure::Policy { subject, policy } => write!(
+                f,
+                "certificate policy not asserted: {}: {}",
+                subject, policy
+            ),
+            PolicyFailure::Malformed {
+                subject,
+                extension,
+                reason,
+            } => write!(
+                f,
+                "malformed extension: {}: {}: {}",
+                subject, extension, reason
+            ),
+        }
+    }
+}
+
+/// Requires paths found valid by the wrapped validator to permit arbitrary extended key usages, and to assert
+/// certificate policies.
+///
+/// The target must list every required usage in its extended key usage extension. Issuers constrain it: an issuer with
+/// the extension must list the usage or `anyExtendedKeyUsage`, while an issuer without it permits every usage.
+///
+/// Every certificate in the path must assert every required policy, with issuers also accepted when they assert
+/// `anyPolicy`. Policy mappings aren't processed.
+///
+/// A self-issued top certificate is treated as a trust anchor, and not checked.
+pub struct PolicyPathValidator<V>
+where
+    V: PathValidator,
+{
+    validator: V,
+    usages: Vec<ObjectIdentifier>,
+    policies: Vec<ObjectIdentifier>,
+}
+
+impl<V> PolicyPathValidator<V>
+where
+    V: PathValidator,
+{
+    /// Constructor takes the validator to wrap, and:
+    /// * `usages` are the required extended key usage OIDs
+    /// * `policies` are the required certificate policy OIDs
+    pub fn new(
+        validator: V,
+        usages: Vec<ObjectIdentifier>,
+        policies: Vec<ObjectIdentifier>,
+    ) -> Self {
+        Self {
+            validator,
+            usages,
+            policies,
+        }
+    }
+
+    // first requirement path fails, if any
+    fn failure(&self, path: &[&crate::Certificate]) -> Option<PolicyFailure> {
+        let mut path = path;
+        if let [rest @ .., top] = path {
+            if !rest.is_empty() && top.tbs_certificate.subject == top.tbs_certificate.issuer {
+                path = rest;
+            }
+        }
+
+        for (i, certificate) in path.iter().enumerate() {
+            let target = i == 0;
+            let subject = || certificate.tbs_certificate.subject.to_string();
+
+            let malformed = |extension: ObjectIdentifier, e: der::Error| PolicyFailure::Malformed {
+                subject: subject(),
+                extension,
+                reason: e.to_string(),
+            };
+
+            let usages = match certificate.tbs_certificate.get::<ExtendedKeyUsage>() {
+                Ok(usages) => usages.map(|(_, usages)| usages.0),
+                Err(_) if self.usages.is_empty() => None,
+                Err(e) => return Some(malformed(ID_CE_EXT_KEY_USAGE, e)),
+            };
+            for usage in self.usages.iter() {
+                let permitted = match usages.as_ref() {
+                    Some(usages) => {
+                        usages.contains(usage)
+                            || (!target && usages.contains(&ANY_EXTENDED_KEY_USAGE))
+                    }
+                    None => !target,
+                };
+                if !permitted {
+                    return Some(PolicyFailure::ExtendedKeyUsage {
+                        subject: subject(),
+                        usage: *usage,
+                    });
+                }
+            }
+
+            let policies = match certificate.tbs_certificate.get::<CertificatePolicies>() {
+                Ok(policies) => policies.map_or_else(Vec::new, |(_, policies)| {
+                    policies
+                        .0
+                        .into_iter()
+                        .map(|p| p.policy_identifier)
+                        .collect()
+                }),
+                Err(_) if self.policies.is_empty() => vec![],
+                Err(e) => return Some(malformed(ID_CE_CERTIFICATE_POLICIES, e)),
+            };
+            for policy in self.policies.iter() {
+                if !policies.contains(policy) && (target || !policies.contains(&ANY_POLICY)) {
+                    return Some(PolicyFailure::Policy {
+                        subject: subject(),
+                        policy: *policy,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<V> PathValidator for PolicyPathValidator<V>
+where
+    V: PathValidator,
+    V::PathValidatorError: Send + Sync + 'static,
+{
+    type PathValidatorError = PolicyPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(self.validate_with_evidence(path, &Evidence::default())?.0)
+    }
+
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        let used = match self
+            .validator
+            .validate_with_evidence(path.clone(), evidence)
+            .map_err(|e| PolicyPathValidatorError::PathValidatorError(Box::new(e)))?
+        {
+            (CertificatePathValidation::NotFound(reason), _) => {
+                return Ok((CertificatePathValidation::NotFound(reason), vec![]));
+            }
+            (CertificatePathValidation::Found, used) => used,
+        };
+
+        match self.failure(path.as_slice()) {
+            Some(failure) => Ok((
+                CertificatePathValidation::NotFound(failure.to_string()),
+                vec![],
+            )),
+            None => Ok((CertificatePathValidation::Found, used)),
+        }
+    }
+
+    fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.validator
+            .check_link(child, issuer)
+            .map_err(|e| PolicyPathValidatorError::PathValidatorError(Box::new(e)))
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.validator.cache_counters()
+    }
+}
+
+impl PathValidatorError for PolicyPathValidatorError {}
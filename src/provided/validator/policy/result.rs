@@ -0,0 +1,46 @@
+use crate::api::PathValidatorError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type PolicyPathValidatorResult<T> = result::Result<T, PolicyPathValidatorError>;
+
+#[derive(Debug)]
+pub enum PolicyPathValidatorError {
+    DerError(der::Error),
+    PathValidatorError(Box<dyn PathValidatorError + Send + Sync>),
+}
+
+impl Display for PolicyPathValidatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyPathValidatorError::DerError(e) => {
+                write!(f, "policy path validator -> der error: {}", e)
+            }
+            PolicyPathValidatorError::PathValidatorError(e) => {
+                write!(f, "policy path validator -> {}", e)
+            }
+        }
+    }
+}
+
+impl Error for PolicyPathValidatorError {}
+
+impl From<der::Error> for PolicyPathValidatorError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<Box<dyn PathValidatorError + Send + Sync>> for PolicyPathValidatorError {
+    fn from(e: Box<dyn PathValidatorError + Send + Sync>) -> Self {
+        Self::PathValidatorError(e)
+    }
+}
+
+impl From<PolicyPathValidatorError> for X509PathFinderError {
+    fn from(e: PolicyPathValidatorError) -> Self {
+        Self::PathValidatorError(Box::new(e))
+    }
+}
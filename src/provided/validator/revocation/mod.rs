@@ -0,0 +1,69 @@
+//! Revocation checking shared by the CRL and OCSP validators
+
+use crate::anchor::TrustAnchor;
+use crate::api::RevocationEvidence;
+use der::Decode;
+use std::future::Future;
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+
+// revocation status of one certificate
+pub(crate) enum Checked<R> {
+    // not subject to checking
+    Skipped,
+    // not revoked, as proven by evidence
+    Good(RevocationEvidence),
+    // path rejected for reason
+    Rejected(R),
+}
+
+// candidate issuer public keys: the next certificate in the path, or trust anchors for the top certificate
+pub(crate) fn issuer_keys(
+    path: &[&crate::Certificate],
+    position: usize,
+    anchors: &[TrustAnchor],
+) -> der::Result<Vec<SubjectPublicKeyInfoOwned>> {
+    let mut keys = vec![];
+    match path.get(position + 1) {
+        Some(issuer) => keys.push(issuer.tbs_certificate.subject_public_key_info.clone()),
+        None => {
+            let issuer = path[position].tbs_certificate.issuer.to_string();
+            for anchor in anchors.iter().filter(|a| a.subject() == issuer) {
+                keys.push(SubjectPublicKeyInfoOwned::from_der(anchor.spki())?);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+// checks each certificate in path with its issuing certificate in the path, if any, and its issuer keys,
+// returning the first reason path is rejected, if any. evidence proving certificates not revoked is appended to `used`
+pub(crate) async fn check_path<'p, R, E, C, F>(
+    path: &'p [&'p crate::Certificate],
+    anchors: &[TrustAnchor],
+    used: &mut Vec<RevocationEvidence>,
+    mut check: C,
+) -> Result<Option<R>, E>
+where
+    C: FnMut(
+        &'p crate::Certificate,
+        Option<&'p crate::Certificate>,
+        Vec<SubjectPublicKeyInfoOwned>,
+    ) -> F,
+    F: Future<Output = Result<Checked<R>, E>>,
+    E: From<der::Error>,
+{
+    for (position, certificate) in path.iter().enumerate() {
+        let keys = issuer_keys(path, position, anchors)?;
+        match check(certificate, path.get(position + 1).copied(), keys).await? {
+            Checked::Skipped => {}
+            Checked::Good(evidence) => {
+                if !used.contains(&evidence) {
+                    used.push(evidence);
+                }
+            }
+            Checked::Rejected(reason) => return Ok(Some(reason)),
+        }
+    }
+
+    Ok(None)
+}
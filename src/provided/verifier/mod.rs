@@ -0,0 +1,4 @@
+//! TLS certificate verifier implementations
+
+#[cfg(feature = "rustls")]
+pub mod rustls;
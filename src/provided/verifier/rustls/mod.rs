@@ -0,0 +1,324 @@
+//! [rustls](https://docs.rs/rustls/0.21/rustls/) certificate verifier, finding paths with [`X509PathFinder`]
+
+pub mod result;
+
+use crate::anchor::TrustAnchor;
+use crate::api::{AsyncPathValidator, CertificatePathValidation, Evidence, RevocationEvidence};
+use crate::provided::validator::identity::{verify_identity, ServerIdentity};
+use crate::provided::verifier::rustls::result::{RustlsVerifierError, RustlsVerifierResult};
+use crate::report::{CacheCounters, CertificateOrigin, Report};
+use crate::store::{MemoryStore, SelfSignedPolicy};
+#[cfg(test)]
+use crate::TestAIA;
+use crate::{X509PathFinder, X509PathFinderConfiguration, X509PathFinderError};
+use ::rustls::client::{ServerCertVerified, ServerCertVerifier};
+use ::rustls::server::{ClientCertVerified, ClientCertVerifier};
+use ::rustls::{CertificateError, DistinguishedName, Error, ServerName};
+use async_trait::async_trait;
+use der::Decode;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tokio::runtime::Runtime;
+#[cfg(not(test))]
+use x509_client::X509ClientConfiguration;
+
+// validator shared by the path finder of each verification
+struct SharedPathValidator<V>(Arc<V>);
+
+#[async_trait]
+impl<V> AsyncPathValidator for SharedPathValidator<V>
+where
+    V: AsyncPathValidator + Send + Sync,
+{
+    type PathValidatorError = V::PathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.0.validate(path).await
+    }
+
+    async fn validate_with_evidence(
+        &self,
+        path: Vec<&crate::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        self.0.validate_with_evidence(path, evidence).await
+    }
+
+    async fn check_link(
+        &self,
+        child: &crate::Certificate,
+        issuer: &crate::Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.0.check_link(child, issuer).await
+    }
+
+    fn cache_counters(&self) -> CacheCounters {
+        self.0.cache_counters()
+    }
+}
+
+/// Implements rustls's [`ServerCertVerifier`] and [`ClientCertVerifier`] with [`X509PathFinder`].
+///
+/// Each verification searches for a path from the presented end-entity certificate, with the presented intermediates
+/// tried before the stored certificates. Missing intermediates are found by following AIA URLs when `aia` is
+/// configured, so servers sending incomplete chains are still verified. Server certificates must also present the
+/// expected server name. See [`verify_identity`].
+///
+/// Verifications share a [`MemoryStore`] of the configured `certificates`, and certificates downloaded from AIA URLs
+/// are cached there for later verifications. Presented intermediates are only searched by the verification they were
+/// presented to.
+///
+/// A stapled OCSP response and SCTs sent by the server are passed to the validator as [`Evidence`], along with the
+/// verification time given by rustls. Handshake signatures are verified by rustls's default implementations.
+///
+/// rustls verifiers are synchronous, so each search runs to completion on a scoped thread, blocking on a
+/// single-threaded runtime shared by every verification. Verification is therefore safe to call from within an async
+/// runtime, such as by `tokio-rustls`, but blocks the calling thread for up to the configured `limit`.
+pub struct RustlsVerifier<V>
+where
+    V: AsyncPathValidator,
+{
+    limit: Duration,
+    #[cfg(not(test))]
+    aia: Option<X509ClientConfiguration>,
+    #[cfg(test)]
+    aia: Option<TestAIA>,
+    validator: Arc<V>,
+    store: Mutex<MemoryStore>,
+    anchors: Vec<TrustAnchor>,
+    horizon: Duration,
+    self_signed: SelfSignedPolicy,
+    root_subjects: Vec<DistinguishedName>,
+    // built by the first verification
+    runtime: OnceLock<Runtime>,
+}
+
+impl<V> RustlsVerifier<V>
+where
+    V: AsyncPathValidator + Send + Sync,
+    X509PathFinderError: From<<V as AsyncPathValidator>::PathValidatorError>,
+    X509PathFinderError: From<Infallible>,
+{
+    /// Instantiate with the [`X509PathFinder`] configuration used by every verification. The subjects of `anchors`
+    /// are sent to clients as acceptable issuers when requesting client authentication.
+    pub fn new(config: X509PathFinderConfiguration<V>) -> Self {
+        let mut store = MemoryStore::new();
+        store.set_self_signed(config.self_signed);
+        Self::with_store(config, store)
+    }
+
+    /// Instantiate with the [`X509PathFinder`] configuration used by every verification, sharing `store` between
+    /// verifications. The configured `certificates` are inserted into `store`. Configure eviction on `store` to bound
+    /// the certificates cached from AIA URLs.
+    pub fn with_store(config: X509PathFinderConfiguration<V>, mut store: MemoryStore) -> Self {
+        for certificate in config.certificates {
+            store.insert(certificate);
+        }
+
+        Self {
+            limit: config.limit,
+            aia: config.aia,
+            validator: Arc::new(config.validator),
+            store: Mutex::new(store),
+            root_subjects: config
+                .anchors
+                .iter()
+                .map(|a| DistinguishedName::from(a.subject_der().to_vec()))
+                .collect(),
+            anchors: config.anchors,
+            horizon: config.horizon,
+            self_signed: config.self_signed,
+            runtime: OnceLock::new(),
+        }
+    }
+
+    /// Store shared by verifications, locked until the guard is dropped
+    pub fn store(&self) -> MutexGuard<'_, MemoryStore> {
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn runtime(&self) -> RustlsVerifierResult<&Runtime> {
+        if let Some(runtime) = self.runtime.get() {
+            return Ok(runtime);
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| RustlsVerifierError::Error(e.to_string()))?;
+        // a concurrent verification may have won, its runtime is used instead
+        let _ = self.runtime.set(runtime);
+        self.runtime
+            .get()
+            .ok_or_else(|| RustlsVerifierError::Error("runtime not built".to_string()))
+    }
+
+    // find path from presented certificates, on a scoped thread so never blocking within a runtime
+    fn find(
+        &self,
+        target: Arc<crate::Certificate>,
+        intermediates: &[::rustls::Certificate],
+        evidence: Evidence,
+    ) -> Result<(), Error> {
+        let mut store = MemoryStore::new();
+        store.set_self_signed(self.self_signed);
+        for certificate in intermediates {
+            store.insert(parse(certificate)?);
+        }
+        store.extend_from(&self.store(), |_| true);
+
+        let runtime = self.runtime()?;
+        let report = thread::scope(|scope| {
+            scope
+                .spawn(|| -> RustlsVerifierResult<Report> {
+                    let mut search = X509PathFinder::with_store(
+                        X509PathFinderConfiguration {
+                            limit: self.limit,
+                            aia: self.aia.clone(),
+                            validator: SharedPathValidator(self.validator.clone()),
+                            certificates: vec![],
+                            anchors: self.anchors.clone(),
+                            horizon: self.horizon,
+                            self_signed: self.self_signed,
+                        },
+                        store,
+                    );
+                    let report = runtime.block_on(search.find_with_evidence(target, evidence))?;
+
+                    // cache downloaded certificates for later verifications
+                    self.store().extend_from(search.store(), |origin| {
+                        matches!(origin, CertificateOrigin::Url(_))
+                    });
+                    Ok(report)
+                })
+                .join()
+                .unwrap_or_else(|_| {
+                    Err(RustlsVerifierError::Error(
+                        "path search panicked".to_string(),
+                    ))
+                })
+        })?;
+
+        match report.found {
+            Some(_) => Ok(()),
+            None => Err(RustlsVerifierError::NotFound(
+                report.failures.into_iter().map(|f| f.reason).collect(),
+            )
+            .into()),
+        }
+    }
+}
+
+// dropping a runtime within another runtime panics, as a verifier held by an async client may be
+impl<V> Drop for RustlsVerifier<V>
+where
+    V: AsyncPathValidator,
+{
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl<V> ServerCertVerifier for RustlsVerifier<V>
+where
+    V: AsyncPathValidator + Send + Sync,
+    X509PathFinderError: From<<V as AsyncPathValidator>::PathValidatorError>,
+    X509PathFinderError: From<Infallible>,
+{
+    fn verify_server_cert(
+        &self,
+        end_entity: &::rustls::Certificate,
+        intermediates: &[::rustls::Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, Error> {
+        let identity = match server_name {
+            ServerName::DnsName(name) => ServerIdentity::parse(name.as_ref())
+                .map_err(|e| RustlsVerifierError::Error(e.to_string()))?,
+            ServerName::IpAddress(ip) => ServerIdentity::Ip(*ip),
+            _ => {
+                return Err(
+                    RustlsVerifierError::Error("unsupported server name".to_string()).into(),
+                )
+            }
+        };
+
+        let target = parse(end_entity)?;
+        verify_identity(&target, &identity)
+            .map_err(|_| Error::InvalidCertificate(CertificateError::NotValidForName))?;
+
+        let mut evidence = Evidence {
+            time: Some(now),
+            ..Evidence::default()
+        };
+        if !ocsp_response.is_empty() {
+            evidence
+                .revocation
+                .push(RevocationEvidence::Ocsp(ocsp_response.to_vec()));
+        }
+        if let Some(list) = sct_list(scts) {
+            evidence.scts.push(list);
+        }
+
+        self.find(Arc::new(target), intermediates, evidence)?;
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+impl<V> ClientCertVerifier for RustlsVerifier<V>
+where
+    V: AsyncPathValidator + Send + Sync,
+    X509PathFinderError: From<<V as AsyncPathValidator>::PathValidatorError>,
+    X509PathFinderError: From<Infallible>,
+{
+    fn client_auth_root_subjects(&self) -> &[DistinguishedName] {
+        self.root_subjects.as_slice()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &::rustls::Certificate,
+        intermediates: &[::rustls::Certificate],
+        now: SystemTime,
+    ) -> Result<ClientCertVerified, Error> {
+        self.find(
+            Arc::new(parse(end_entity)?),
+            intermediates,
+            Evidence {
+                time: Some(now),
+                ..Evidence::default()
+            },
+        )?;
+        Ok(ClientCertVerified::assertion())
+    }
+}
+
+fn parse(certificate: &::rustls::Certificate) -> Result<crate::Certificate, Error> {
+    crate::Certificate::from_der(certificate.0.as_slice())
+        .map_err(|_| Error::InvalidCertificate(CertificateError::BadEncoding))
+}
+
+// SignedCertificateTimestampList of the scts sent by the server, rfc 6962 section 3.3
+fn sct_list(scts: &mut dyn Iterator<Item = &[u8]>) -> Option<Vec<u8>> {
+    let mut list = vec![];
+    for sct in scts {
+        list.extend((sct.len() as u16).to_be_bytes());
+        list.extend(sct);
+    }
+    if list.is_empty() {
+        return None;
+    }
+    let mut encoded = (list.len() as u16).to_be_bytes().to_vec();
+    encoded.extend(list);
+    Some(encoded)
+}
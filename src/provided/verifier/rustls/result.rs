@@ -0,0 +1,50 @@
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type RustlsVerifierResult<T> = result::Result<T, RustlsVerifierError>;
+
+#[derive(Debug)]
+pub enum RustlsVerifierError {
+    /// No valid path found, with the reason each candidate path was rejected
+    NotFound(Vec<String>),
+    Error(String),
+}
+
+impl Display for RustlsVerifierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RustlsVerifierError::NotFound(reasons) => {
+                write!(
+                    f,
+                    "rustls verifier -> path not found: {}",
+                    reasons.join("; ")
+                )
+            }
+            RustlsVerifierError::Error(e) => {
+                write!(f, "rustls verifier -> error: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for RustlsVerifierError {}
+
+// path finder errors aren't Send + Sync, as rustls requires
+impl From<X509PathFinderError> for RustlsVerifierError {
+    fn from(e: X509PathFinderError) -> Self {
+        Self::Error(e.to_string())
+    }
+}
+
+impl From<RustlsVerifierError> for rustls::Error {
+    fn from(e: RustlsVerifierError) -> Self {
+        match e {
+            RustlsVerifierError::NotFound(_) => rustls::Error::InvalidCertificate(
+                rustls::CertificateError::Other(std::sync::Arc::new(e)),
+            ),
+            RustlsVerifierError::Error(e) => rustls::Error::General(e),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! Certificate path search report
 
+use crate::api::RevocationEvidence;
+use std::ops::{Add, Sub};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use url::Url;
 
 pub struct PathIter<'r> {
@@ -31,6 +33,13 @@ pub struct Report {
     pub duration: Duration,
     /// Any validation failures reported by [`PathValidator`](crate::api::PathValidator)
     pub failures: Vec<ValidationFailure>,
+    /// Any links vetoed by [`check_link`](crate::api::PathValidator::check_link). Each path ends with the rejected issuer.
+    pub vetoes: Vec<ValidationFailure>,
+    /// Validation cache hits and misses during path search. See [`cache_counters`](crate::api::PathValidator::cache_counters).
+    pub cache: CacheCounters,
+    /// Valid paths passed over because they expire within the configured
+    /// [`horizon`](crate::X509PathFinderConfiguration::horizon), while a longer lived path was searched for
+    pub expiring: Vec<ValidationFailure>,
 }
 
 /// Path search success
@@ -40,6 +49,13 @@ pub struct Found {
     pub path: Vec<Arc<crate::Certificate>>,
     /// Certificate path origins
     pub origin: Vec<CertificateOrigin>,
+    /// SHA-256 fingerprints of the DER-encoded path certificates. See [`fingerprint`](crate::store::fingerprint).
+    pub fingerprints: Vec<Vec<u8>>,
+    /// Revocation evidence that proved the path not revoked, whether supplied or fetched.
+    /// Empty when no revocation checking validator is used.
+    pub revocation: Vec<RevocationEvidence>,
+    /// Earliest expiry across the path
+    pub expiry: Expiry,
 }
 
 impl<'r> IntoIterator for &'r Found {
@@ -54,6 +70,18 @@ impl<'r> IntoIterator for &'r Found {
     }
 }
 
+/// Earliest expiry across a found path
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Expiry {
+    /// Earliest `notAfter` in the path, when the path stops being valid
+    pub not_after: SystemTime,
+    /// Index into [`Found::path`](crate::report::Found::path) of the certificate expiring first
+    pub certificate: usize,
+    /// Path expires within the configured [`horizon`](crate::X509PathFinderConfiguration::horizon).
+    /// Only found when no valid path outlives the horizon.
+    pub within_horizon: bool,
+}
+
 /// Origins of each certificate found in path
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum CertificateOrigin {
@@ -61,9 +89,9 @@ pub enum CertificateOrigin {
     Unknown,
     /// Certificate used in [`X509PathFinder::find`](crate::find::X509PathFinder::find)
     Target,
-    /// Certificate found in store
+    /// Certificate found in store, inserted directly
     Store,
-    /// Certificate downloaded from AIA url
+    /// Certificate downloaded from AIA url, during this search or cached in the store by an earlier one
     Url(Url),
 }
 
@@ -74,6 +102,8 @@ pub struct ValidationFailure {
     pub path: Vec<Arc<crate::Certificate>>,
     /// Path origins where validation failure occurred
     pub origin: Vec<CertificateOrigin>,
+    /// SHA-256 fingerprints of the DER-encoded path certificates. See [`fingerprint`](crate::store::fingerprint).
+    pub fingerprints: Vec<Vec<u8>>,
     /// Human-readable reason for validation failure
     pub reason: String,
 }
@@ -89,3 +119,34 @@ impl<'r> IntoIterator for &'r ValidationFailure {
         }
     }
 }
+
+/// Validation cache hit and miss counters
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct CacheCounters {
+    /// Validations answered from cache
+    pub hits: usize,
+    /// Validations passed through to the cached validator
+    pub misses: usize,
+}
+
+impl Add for CacheCounters {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            hits: self.hits + rhs.hits,
+            misses: self.misses + rhs.misses,
+        }
+    }
+}
+
+impl Sub for CacheCounters {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            hits: self.hits.saturating_sub(rhs.hits),
+            misses: self.misses.saturating_sub(rhs.misses),
+        }
+    }
+}
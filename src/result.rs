@@ -1,4 +1,5 @@
-use crate::api::PathValidatorError;
+use crate::api::{CertificateBackendError, PathValidatorError};
+use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::result;
@@ -17,6 +18,8 @@ pub enum X509PathFinderError {
     X509ClientError(X509ClientError),
     /// [`PathValidator`](crate::api::PathValidator) errors
     PathValidatorError(Box<dyn PathValidatorError>),
+    /// [`CertificateBackend`](crate::api::CertificateBackend) errors
+    CertificateBackendError(Box<dyn CertificateBackendError>),
 }
 
 impl Display for X509PathFinderError {
@@ -34,6 +37,9 @@ impl Display for X509PathFinderError {
             X509PathFinderError::PathValidatorError(e) => {
                 write!(f, "x509-path-finder -> {}", e)
             }
+            X509PathFinderError::CertificateBackendError(e) => {
+                write!(f, "x509-path-finder -> {}", e)
+            }
         }
     }
 }
@@ -57,3 +63,15 @@ impl From<Box<dyn PathValidatorError>> for X509PathFinderError {
         Self::PathValidatorError(e)
     }
 }
+
+impl From<Box<dyn CertificateBackendError>> for X509PathFinderError {
+    fn from(e: Box<dyn CertificateBackendError>) -> Self {
+        Self::CertificateBackendError(e)
+    }
+}
+
+impl From<Infallible> for X509PathFinderError {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
@@ -0,0 +1,130 @@
+use der::asn1::BitString;
+use der::oid::db::rfc5912::{
+    ECDSA_WITH_SHA_256, ECDSA_WITH_SHA_384, ID_EC_PUBLIC_KEY, RSA_ENCRYPTION, SECP_256_R_1,
+    SECP_384_R_1, SHA_256_WITH_RSA_ENCRYPTION, SHA_384_WITH_RSA_ENCRYPTION,
+    SHA_512_WITH_RSA_ENCRYPTION,
+};
+use der::oid::db::rfc8410::ID_ED_25519;
+use der::oid::ObjectIdentifier;
+use der::Encode;
+use webpki::{
+    EndEntityCert, SignatureAlgorithm, ECDSA_P256_SHA256, ECDSA_P256_SHA384, ECDSA_P384_SHA256,
+    ECDSA_P384_SHA384, ED25519, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_2048_8192_SHA384,
+    RSA_PKCS1_2048_8192_SHA512,
+};
+use x509_cert::certificate::{TbsCertificate, Version};
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::time::{Time, Validity};
+
+// every supported signature algorithm
+pub static ALGORITHMS: &[&SignatureAlgorithm] = &[
+    &ECDSA_P256_SHA256,
+    &ECDSA_P256_SHA384,
+    &ECDSA_P384_SHA256,
+    &ECDSA_P384_SHA384,
+    &RSA_PKCS1_2048_8192_SHA256,
+    &RSA_PKCS1_2048_8192_SHA384,
+    &RSA_PKCS1_2048_8192_SHA512,
+    &ED25519,
+];
+
+// verifies `signature` over `message`, signed by `spki` with signature `algorithm`, one of the trusted `algorithms`
+pub fn verify(
+    algorithms: &[&SignatureAlgorithm],
+    spki: &SubjectPublicKeyInfoOwned,
+    algorithm: &ObjectIdentifier,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), String> {
+    let oid = algorithm;
+    let algorithm = signature_algorithm(spki, algorithm)?;
+    if !algorithms.iter().any(|a| std::ptr::eq(*a, algorithm)) {
+        return Err(format!("untrusted signature algorithm {}", oid));
+    }
+
+    // webpki only verifies with the key of a parsed certificate, so carry the key in an unsigned one
+    let carrier = carrier(spki).map_err(|e| e.to_string())?;
+    EndEntityCert::try_from(carrier.as_slice())
+        .map_err(|e| format!("malformed public key: {:?}", e))?
+        .verify_signature(algorithm, message, signature)
+        .map_err(|_| "signature not verified".to_string())
+}
+
+// certificate signed by `spki`, with one of the trusted `algorithms`
+pub fn verify_certificate(
+    algorithms: &[&SignatureAlgorithm],
+    spki: &SubjectPublicKeyInfoOwned,
+    certificate: &crate::Certificate,
+) -> Result<(), String> {
+    let tbs = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| e.to_string())?;
+    let signature = certificate
+        .signature
+        .as_bytes()
+        .ok_or_else(|| "malformed signature".to_string())?;
+    verify(
+        algorithms,
+        spki,
+        &certificate.signature_algorithm.oid,
+        &tbs,
+        signature,
+    )
+}
+
+// DER encoded certificate with `spki` and no meaningful other content
+fn carrier(spki: &SubjectPublicKeyInfoOwned) -> der::Result<Vec<u8>> {
+    let algorithm = AlgorithmIdentifierOwned {
+        oid: ECDSA_WITH_SHA_256,
+        parameters: None,
+    };
+    x509_cert::Certificate {
+        tbs_certificate: TbsCertificate {
+            version: Version::V3,
+            serial_number: SerialNumber::new(&[1])?,
+            signature: algorithm.clone(),
+            issuer: Name::default(),
+            validity: Validity {
+                not_before: Time::INFINITY,
+                not_after: Time::INFINITY,
+            },
+            subject: Name::default(),
+            subject_public_key_info: spki.clone(),
+            issuer_unique_id: None,
+            subject_unique_id: None,
+            extensions: None,
+        },
+        signature_algorithm: algorithm,
+        signature: BitString::from_bytes(&[])?,
+    }
+    .to_der()
+}
+
+fn signature_algorithm(
+    spki: &SubjectPublicKeyInfoOwned,
+    algorithm: &ObjectIdentifier,
+) -> Result<&'static SignatureAlgorithm, String> {
+    let key = &spki.algorithm;
+    let curve = if key.oid == ID_EC_PUBLIC_KEY {
+        key.parameters
+            .as_ref()
+            .and_then(|p| p.decode_as::<ObjectIdentifier>().ok())
+    } else {
+        None
+    };
+
+    match (key.oid, curve, *algorithm) {
+        (ID_EC_PUBLIC_KEY, Some(SECP_256_R_1), ECDSA_WITH_SHA_256) => Ok(&ECDSA_P256_SHA256),
+        (ID_EC_PUBLIC_KEY, Some(SECP_256_R_1), ECDSA_WITH_SHA_384) => Ok(&ECDSA_P256_SHA384),
+        (ID_EC_PUBLIC_KEY, Some(SECP_384_R_1), ECDSA_WITH_SHA_256) => Ok(&ECDSA_P384_SHA256),
+        (ID_EC_PUBLIC_KEY, Some(SECP_384_R_1), ECDSA_WITH_SHA_384) => Ok(&ECDSA_P384_SHA384),
+        (RSA_ENCRYPTION, _, SHA_256_WITH_RSA_ENCRYPTION) => Ok(&RSA_PKCS1_2048_8192_SHA256),
+        (RSA_ENCRYPTION, _, SHA_384_WITH_RSA_ENCRYPTION) => Ok(&RSA_PKCS1_2048_8192_SHA384),
+        (RSA_ENCRYPTION, _, SHA_512_WITH_RSA_ENCRYPTION) => Ok(&RSA_PKCS1_2048_8192_SHA512),
+        (ID_ED_25519, _, ID_ED_25519) => Ok(&ED25519),
+        _ => Err(format!("unsupported signature algorithm {}", algorithm)),
+    }
+}
@@ -0,0 +1,170 @@
+//! Conformance suite for [`CertificateBackend`] implementations.
+//!
+//! Each check takes an empty store in its default configuration and panics if the store misbehaves, so backends can run the suite from their own
+//! tests:
+//!
+//! ```
+//! # tokio_test();
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn tokio_test() {
+//! use x509_path_finder::store::{conformance, MemoryStore};
+//!
+//! conformance::run(MemoryStore::new).await;
+//! # }
+//! ```
+//!
+//! Certificates are synthetic and unsigned, as stores index certificates by name and never verify signatures.
+
+use crate::api::CertificateBackend;
+use crate::report::CertificateOrigin;
+use der::asn1::BitString;
+use der::oid::ObjectIdentifier;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use x509_cert::certificate::{TbsCertificate, Version};
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::time::Validity;
+
+// ecdsa-with-SHA256
+const ALGORITHM: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+
+/// Run every check, each against a new empty store from `new`
+pub async fn run<S, F>(mut new: F)
+where
+    S: CertificateBackend,
+    F: FnMut() -> S,
+{
+    issuers(new()).await;
+    insert(new()).await;
+    self_signed(new()).await;
+    priority(new()).await;
+    insert_from_url(new()).await;
+}
+
+/// Only certificates whose subject is the issuer of the certificate are returned, with the inserted origin
+pub async fn issuers<S: CertificateBackend>(mut store: S) {
+    let ee = certificate("CN=ee", "CN=issuer", 1);
+    let issuer = certificate("CN=issuer", "CN=root", 2);
+    let unrelated = certificate("CN=unrelated", "CN=root", 3);
+
+    assert!(issuers_of(&store, &ee).await.is_empty());
+    assert!(insert_into(&mut store, unrelated).await);
+    assert!(issuers_of(&store, &ee).await.is_empty());
+    assert!(insert_into(&mut store, issuer.clone()).await);
+    assert_eq!(
+        vec![(issuer, CertificateOrigin::Store)],
+        store
+            .issuers(&ee)
+            .await
+            .unwrap_or_else(|e| panic!("issuers: {}", e))
+    );
+}
+
+/// Inserting a stored certificate again is refused, and never duplicates it
+pub async fn insert<S: CertificateBackend>(mut store: S) {
+    let ee = certificate("CN=ee", "CN=issuer", 1);
+    let issuer = certificate("CN=issuer", "CN=root", 2);
+
+    assert!(insert_into(&mut store, issuer.clone()).await);
+    assert!(!insert_into(&mut store, issuer.clone()).await);
+    assert!(!insert_into(&mut store, Arc::new(issuer.as_ref().clone())).await);
+    assert_eq!(vec![issuer], issuers_of(&store, &ee).await);
+}
+
+/// Self-signed certificates are refused by default, and never returned. See
+/// [`SelfSignedPolicy`](crate::store::SelfSignedPolicy).
+pub async fn self_signed<S: CertificateBackend>(mut store: S) {
+    let root = certificate("CN=root", "CN=root", 1);
+
+    assert!(!insert_into(&mut store, root.clone()).await);
+    assert!(issuers_of(&store, &root).await.is_empty());
+}
+
+/// Issuers are returned in insertion order, earliest first
+pub async fn priority<S: CertificateBackend>(mut store: S) {
+    let ee = certificate("CN=ee", "CN=issuer", 1);
+    let issuers = (2..6)
+        .map(|serial| certificate("CN=issuer", "CN=root", serial))
+        .collect::<Vec<Arc<crate::Certificate>>>();
+
+    for issuer in issuers.iter() {
+        assert!(insert_into(&mut store, issuer.clone()).await);
+    }
+    assert_eq!(issuers, issuers_of(&store, &ee).await);
+}
+
+/// Certificates downloaded from AIA URLs are inserted like any other
+pub async fn insert_from_url<S: CertificateBackend>(mut store: S) {
+    let ee = certificate("CN=ee", "CN=issuer", 1);
+    let issuers = (2..4)
+        .map(|serial| certificate("CN=issuer", "CN=root", serial))
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let url = Url::parse("http://example.com/issuer.cer").unwrap();
+
+    assert!(insert_into(&mut store, issuers[0].clone()).await);
+    assert!(store
+        .insert_from_url(issuers[1].clone(), &url)
+        .await
+        .unwrap_or_else(|e| panic!("insert from url: {}", e)));
+    assert!(!store
+        .insert_from_url(issuers[1].clone(), &url)
+        .await
+        .unwrap_or_else(|e| panic!("insert from url: {}", e)));
+    assert_eq!(issuers, issuers_of(&store, &ee).await);
+}
+
+async fn issuers_of<S: CertificateBackend>(
+    store: &S,
+    certificate: &crate::Certificate,
+) -> Vec<Arc<crate::Certificate>> {
+    store
+        .issuers(certificate)
+        .await
+        .unwrap_or_else(|e| panic!("issuers: {}", e))
+        .into_iter()
+        .map(|(certificate, _)| certificate)
+        .collect()
+}
+
+async fn insert_into<S: CertificateBackend>(
+    store: &mut S,
+    certificate: Arc<crate::Certificate>,
+) -> bool {
+    store
+        .insert(certificate)
+        .await
+        .unwrap_or_else(|e| panic!("insert: {}", e))
+}
+
+// unsigned certificate, unique by serial
+fn certificate(subject: &str, issuer: &str, serial: u8) -> Arc<crate::Certificate> {
+    let algorithm = AlgorithmIdentifierOwned {
+        oid: ALGORITHM,
+        parameters: None,
+    };
+    let bits = BitString::from_bytes(&[serial]).unwrap();
+
+    Arc::new(crate::Certificate {
+        tbs_certificate: TbsCertificate {
+            version: Version::V3,
+            serial_number: SerialNumber::from(serial),
+            signature: algorithm.clone(),
+            issuer: Name::from_str(issuer).unwrap(),
+            validity: Validity::from_now(Duration::from_secs(86400)).unwrap(),
+            subject: Name::from_str(subject).unwrap(),
+            subject_public_key_info: SubjectPublicKeyInfoOwned {
+                algorithm: algorithm.clone(),
+                subject_public_key: bits.clone(),
+            },
+            issuer_unique_id: None,
+            subject_unique_id: None,
+            extensions: None,
+        },
+        signature_algorithm: algorithm,
+        signature: bits,
+    })
+}
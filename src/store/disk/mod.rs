@@ -0,0 +1,332 @@
+//! [`CertificateBackend`] persisted in a local directory
+
+pub mod result;
+
+use crate::api::CertificateBackend;
+use crate::certificate::Certificate;
+use crate::load::Skipped;
+use crate::report::CertificateOrigin;
+use crate::store::disk::result::DiskStoreResult;
+use crate::store::SelfSignedPolicy;
+use async_trait::async_trait;
+use der::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+/// Certificate persisted in [`DiskStore`], with origin metadata
+#[derive(Clone, Debug)]
+pub struct DiskEntry {
+    /// Stored certificate
+    pub certificate: Arc<crate::Certificate>,
+    /// SHA-256 fingerprint of the certificate. See [`fingerprint`](crate::store::fingerprint).
+    pub fingerprint: Vec<u8>,
+    /// AIA URL the certificate was downloaded from, if not inserted directly
+    pub url: Option<Url>,
+    /// When the certificate was inserted, or fetched if downloaded
+    pub added: SystemTime,
+}
+
+// entry with its parsed names, compared as strings when searching for issuers
+struct Stored {
+    priority: u64,
+    certificate: Certificate,
+    entry: DiskEntry,
+}
+
+// persisted alongside each certificate
+#[derive(Deserialize, Serialize)]
+struct Metadata {
+    priority: u64,
+    url: Option<String>,
+    added: u64,
+}
+
+impl Metadata {
+    fn new(priority: u64, entry: &DiskEntry) -> Self {
+        Self {
+            priority,
+            url: entry.url.as_ref().map(|u| u.to_string()),
+            added: entry
+                .added
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+}
+
+/// [`CertificateBackend`] persisting every inserted certificate in a local directory, including certificates cached
+/// from AIA URLs, and reloading them in the same priority order on open. Certificates are deduplicated by SHA-256
+/// fingerprint, and self-signed certificates are not stored by default. See [`set_self_signed`](Self::set_self_signed).
+///
+/// Each certificate is written as `<fingerprint>.der`, alongside `<fingerprint>.json` metadata recording its priority
+/// and origin. Files are synced and renamed into place, so an interrupted insert never leaves a partial entry, and
+/// temporary files left by one are deleted on open.
+///
+/// The store is synchronous: its [`CertificateBackend`] methods perform blocking file I/O on the polling thread rather
+/// than yielding. Search with [`find_blocking`](crate::X509PathFinder::find_blocking), or on a multi-threaded runtime
+/// where blocking a worker for each insert is acceptable.
+pub struct DiskStore {
+    path: PathBuf,
+    // in priority order
+    entries: Vec<Stored>,
+    // fingerprint to index in entries
+    fingerprints: HashMap<Vec<u8>, usize>,
+    serial: u64,
+    skipped: Vec<Skipped>,
+    update_origin: bool,
+    self_signed: SelfSignedPolicy,
+}
+
+impl DiskStore {
+    /// Open store in directory at `path`, creating it if missing, and reload persisted certificates in priority order.
+    /// Malformed entries are skipped. See [`skipped`](Self::skipped).
+    pub fn open<P: AsRef<Path>>(path: P) -> DiskStoreResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(&path)?;
+
+        let mut store = Self {
+            path,
+            entries: vec![],
+            fingerprints: HashMap::new(),
+            serial: 0,
+            skipped: vec![],
+            update_origin: false,
+            self_signed: SelfSignedPolicy::Exclude,
+        };
+
+        let mut names = fs::read_dir(&store.path)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<Result<Vec<PathBuf>, std::io::Error>>()?;
+        names.sort();
+
+        let mut entries = vec![];
+        for name in names {
+            let der = match name.extension().and_then(|e| e.to_str()) {
+                Some("der") => name,
+                // left by an interrupted insert
+                Some("tmp") => {
+                    fs::remove_file(name)?;
+                    continue;
+                }
+                _ => continue,
+            };
+            match store.read_entry(der.as_path()) {
+                Ok(entry) => entries.push(entry),
+                Err(reason) => store.skipped.push(Skipped {
+                    path: Some(der),
+                    block: None,
+                    reason,
+                }),
+            }
+        }
+
+        entries.sort_by_key(|stored: &Stored| stored.priority);
+        for stored in entries {
+            store.serial = store.serial.max(stored.priority);
+            store
+                .fingerprints
+                .insert(stored.entry.fingerprint.clone(), store.entries.len());
+            store.entries.push(stored);
+        }
+
+        Ok(store)
+    }
+
+    /// Store directory
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Number of stored certificates
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Store is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over stored certificates, in priority order
+    pub fn iter(&self) -> impl Iterator<Item = &DiskEntry> + '_ {
+        self.entries.iter().map(|s| &s.entry)
+    }
+
+    /// Certificate with SHA-256 `fingerprint`. See [`fingerprint`](crate::store::fingerprint).
+    pub fn get(&self, fingerprint: &[u8]) -> Option<&DiskEntry> {
+        self.fingerprints
+            .get(fingerprint)
+            .map(|index| &self.entries[*index].entry)
+    }
+
+    /// When `update` is `true`, a stored certificate downloaded again records the latest AIA URL and fetch time.
+    /// Otherwise the first origin is kept. Priority is never changed. Defaults to `false`.
+    pub fn set_update_origin(&mut self, update: bool) {
+        self.update_origin = update;
+    }
+
+    /// Store self-signed certificates when `policy` is [`SelfSignedPolicy::Include`]. Defaults to
+    /// [`SelfSignedPolicy::Exclude`]. Persisted self-signed certificates are reloaded regardless.
+    pub fn set_self_signed(&mut self, policy: SelfSignedPolicy) {
+        self.self_signed = policy;
+    }
+
+    /// Malformed entries skipped when opening the store
+    pub fn skipped(&self) -> &[Skipped] {
+        self.skipped.as_slice()
+    }
+
+    /// Insert and persist `certificate` with the lowest priority. Returns `false` if the certificate is already stored, or
+    /// self-signed and excluded.
+    pub fn insert<I: Into<Arc<crate::Certificate>>>(
+        &mut self,
+        certificate: I,
+    ) -> DiskStoreResult<bool> {
+        self.persist(certificate.into(), None)
+    }
+
+    fn persist(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+        url: Option<Url>,
+    ) -> DiskStoreResult<bool> {
+        let parsed = Certificate::try_from(certificate.clone())?;
+        if self.self_signed == SelfSignedPolicy::Exclude && parsed.issued(&parsed) {
+            return Ok(false);
+        }
+        let fingerprint = parsed.fingerprint().to_vec();
+        let name = hex(fingerprint.as_slice());
+        let added = SystemTime::now();
+
+        // exact duplicate
+        if let Some(index) = self.fingerprints.get(&fingerprint) {
+            if self.update_origin && url.is_some() {
+                let stored = &mut self.entries[*index];
+                stored.entry.url = url;
+                stored.entry.added = added;
+                let metadata = Metadata::new(stored.priority, &stored.entry);
+                self.write(name.as_str(), "json", serde_json::to_vec(&metadata)?)?;
+            }
+            return Ok(false);
+        }
+
+        let entry = DiskEntry {
+            certificate,
+            fingerprint,
+            url,
+            added,
+        };
+        let metadata = Metadata::new(self.serial + 1, &entry);
+
+        // metadata written last, completing the entry
+        self.write(name.as_str(), "der", entry.certificate.to_der()?)?;
+        self.write(name.as_str(), "json", serde_json::to_vec(&metadata)?)?;
+
+        self.serial += 1;
+        self.fingerprints
+            .insert(entry.fingerprint.clone(), self.entries.len());
+        self.entries.push(Stored {
+            priority: self.serial,
+            certificate: parsed,
+            entry,
+        });
+        Ok(true)
+    }
+
+    // durable once returned: file synced before renaming into place, then the rename synced with the directory
+    fn write(&self, name: &str, extension: &str, data: Vec<u8>) -> DiskStoreResult<()> {
+        let temporary = self.path.join(format!("{}.{}.tmp", name, extension));
+        let mut file = File::create(&temporary)?;
+        file.write_all(data.as_slice())?;
+        file.sync_all()?;
+        fs::rename(temporary, self.path.join(format!("{}.{}", name, extension)))?;
+        // directories can't be opened for syncing on windows
+        #[cfg(unix)]
+        File::open(&self.path)?.sync_all()?;
+        Ok(())
+    }
+
+    fn read_entry(&self, der: &Path) -> Result<Stored, String> {
+        let data = fs::read(der).map_err(|e| e.to_string())?;
+        let certificate = crate::Certificate::from_der(data.as_slice())
+            .map_err(|e| format!("invalid certificate: {}", e))?;
+        let certificate =
+            Certificate::try_from(Arc::new(certificate)).map_err(|e| e.to_string())?;
+        let fingerprint = certificate.fingerprint().to_vec();
+        if der.file_stem().and_then(|s| s.to_str()) != Some(hex(fingerprint.as_slice()).as_str()) {
+            return Err("fingerprint mismatch".to_string());
+        }
+
+        let metadata =
+            fs::read(der.with_extension("json")).map_err(|e| format!("missing metadata: {}", e))?;
+        let metadata: Metadata = serde_json::from_slice(metadata.as_slice())
+            .map_err(|e| format!("invalid metadata: {}", e))?;
+        let url = metadata
+            .url
+            .map(|u| Url::parse(u.as_str()))
+            .transpose()
+            .map_err(|e| format!("invalid metadata url: {}", e))?;
+
+        Ok(Stored {
+            priority: metadata.priority,
+            entry: DiskEntry {
+                certificate: certificate.inner().clone(),
+                fingerprint,
+                url,
+                added: SystemTime::UNIX_EPOCH + Duration::from_secs(metadata.added),
+            },
+            certificate,
+        })
+    }
+}
+
+#[async_trait]
+impl CertificateBackend for DiskStore {
+    type CertificateBackendError = result::DiskStoreError;
+
+    async fn issuers(
+        &self,
+        certificate: &crate::Certificate,
+    ) -> Result<Vec<(Arc<crate::Certificate>, CertificateOrigin)>, Self::CertificateBackendError>
+    {
+        let issuer = certificate.tbs_certificate.issuer.to_string();
+        Ok(self
+            .entries
+            .iter()
+            .filter(|s| s.certificate.subject() == issuer)
+            .map(|s| {
+                let origin = match s.entry.url.as_ref() {
+                    Some(url) => CertificateOrigin::Url(url.clone()),
+                    None => CertificateOrigin::Store,
+                };
+                (s.entry.certificate.clone(), origin)
+            })
+            .collect())
+    }
+
+    async fn insert(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        self.persist(certificate, None)
+    }
+
+    async fn insert_from_url(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+        url: &Url,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        self.persist(certificate, Some(url.clone()))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,62 @@
+use crate::api::CertificateBackendError;
+use crate::X509PathFinderError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type DiskStoreResult<T> = result::Result<T, DiskStoreError>;
+
+/// Errors from [`DiskStore`](crate::store::disk::DiskStore)
+#[derive(Debug)]
+pub enum DiskStoreError {
+    /// Errors when reading or writing the store directory
+    IoError(std::io::Error),
+    /// Errors when encoding certificates
+    DerError(der::Error),
+    /// Errors when encoding metadata
+    JsonError(serde_json::Error),
+}
+
+impl Display for DiskStoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiskStoreError::IoError(e) => {
+                write!(f, "disk store -> io error: {}", e)
+            }
+            DiskStoreError::DerError(e) => {
+                write!(f, "disk store -> der error: {}", e)
+            }
+            DiskStoreError::JsonError(e) => {
+                write!(f, "disk store -> json error: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for DiskStoreError {}
+
+impl CertificateBackendError for DiskStoreError {}
+
+impl From<std::io::Error> for DiskStoreError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+impl From<der::Error> for DiskStoreError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+impl From<serde_json::Error> for DiskStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::JsonError(e)
+    }
+}
+
+impl From<DiskStoreError> for X509PathFinderError {
+    fn from(e: DiskStoreError) -> Self {
+        Self::CertificateBackendError(Box::new(e))
+    }
+}
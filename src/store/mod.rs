@@ -0,0 +1,561 @@
+//! Certificate stores searched for issuer candidates
+
+pub mod conformance;
+#[cfg(feature = "persist")]
+pub mod disk;
+pub mod result;
+
+use crate::api::CertificateBackend;
+use crate::certificate::Certificate;
+use crate::report::CertificateOrigin;
+#[cfg(feature = "persist")]
+use crate::store::result::{MemoryStoreError, MemoryStoreResult};
+use async_trait::async_trait;
+#[cfg(feature = "persist")]
+use base64::{engine::general_purpose::STANDARD, Engine};
+#[cfg(feature = "persist")]
+use der::Decode;
+use der::Encode;
+#[cfg(feature = "persist")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use url::Url;
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::name::Name;
+
+/// SHA-256 fingerprint of the DER-encoded `certificate`, identifying it in [`MemoryStore`]
+pub fn fingerprint(certificate: &crate::Certificate) -> der::Result<Vec<u8>> {
+    Ok(Sha256::digest(certificate.to_der()?).to_vec())
+}
+
+/// Handling of self-signed certificates, such as roots, supplied in
+/// [`X509PathFinderConfiguration::certificates`](crate::X509PathFinderConfiguration::certificates), inserted into a store
+/// or downloaded from AIA URLs
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SelfSignedPolicy {
+    /// Never store self-signed certificates, or include them in paths. Paths end with the certificate issued by a
+    /// trust anchor, which the validator supplies.
+    #[default]
+    Exclude,
+    /// Store self-signed certificates, and include them as the final element of paths, for validators expecting the
+    /// root in the path
+    Include,
+}
+
+/// Order in which [`MemoryStore`] evicts certificates downloaded from AIA URLs when over its bounds
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EvictionPolicy {
+    /// Least recently returned as an issuer candidate first
+    #[default]
+    Lru,
+    /// Least often returned as an issuer candidate first, then least recently
+    Lfu,
+}
+
+/// [`MemoryStore`] bounds and eviction. Only certificates downloaded from AIA URLs are evicted to meet the bounds,
+/// configured and directly inserted certificates are pinned, and may keep the store over its bounds. A downloaded
+/// certificate is kept by its own insert, evicting others, unless no other certificate is evictable.
+#[derive(Clone, Debug, Default)]
+pub struct EvictionConfiguration {
+    /// Maximum number of stored certificates. Zero is unbounded.
+    pub max_certificates: usize,
+    /// Maximum total size of stored DER-encoded certificates in bytes. Zero is unbounded.
+    pub max_bytes: usize,
+    /// Order of eviction
+    pub policy: EvictionPolicy,
+    /// Evict every certificate past its `notAfter`, pinned or not. Expired certificates are never returned as issuer
+    /// candidates.
+    pub expired: bool,
+}
+
+// snapshot format version
+#[cfg(feature = "persist")]
+const SNAPSHOT_VERSION: u32 = 2;
+
+// serialized store, certificates in priority order
+#[cfg(feature = "persist")]
+#[derive(Deserialize, Serialize)]
+struct Snapshot {
+    version: u32,
+    certificates: Vec<SnapshotEntry>,
+}
+
+#[cfg(feature = "persist")]
+#[derive(Deserialize, Serialize)]
+struct SnapshotEntry {
+    // base64 encoded der
+    certificate: String,
+    // aia url, if downloaded or its origin updated
+    url: Option<String>,
+    // inserted directly rather than downloaded, regardless of url
+    pinned: bool,
+}
+
+// stored certificate with usage, tracked through shared references
+struct Entry {
+    certificate: Certificate,
+    // inserted directly rather than downloaded, kept when the origin is updated
+    pinned: bool,
+    size: usize,
+    used: AtomicU64,
+    hits: AtomicU64,
+}
+
+impl Entry {
+    fn expired(&self, now: SystemTime) -> bool {
+        self.certificate
+            .inner()
+            .tbs_certificate
+            .validity
+            .not_after
+            .to_system_time()
+            < now
+    }
+}
+
+impl Clone for Entry {
+    fn clone(&self) -> Self {
+        Self {
+            certificate: self.certificate.clone(),
+            pinned: self.pinned,
+            size: self.size,
+            used: AtomicU64::new(self.used.load(Ordering::Relaxed)),
+            hits: AtomicU64::new(self.hits.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// In-memory [`CertificateBackend`], the default store of [`X509PathFinder`](crate::X509PathFinder). Holds intermediate,
+/// bridge and cross-signed certificates searched for issuer candidates, in priority order. Certificates downloaded
+/// from AIA URLs during a search are cached here too, recording their origin. Certificates are identified by
+/// [`fingerprint`], so exact duplicates are ignored. Self-signed certificates are not stored by default. See
+/// [`set_self_signed`](Self::set_self_signed).
+///
+/// Unbounded by default. See [`set_eviction`](Self::set_eviction) to bound the store for long-lived searches.
+pub struct MemoryStore {
+    // keyed by priority
+    certificates: BTreeMap<usize, Entry>,
+    // fingerprint to priority
+    fingerprints: HashMap<Vec<u8>, usize>,
+    serial: usize,
+    update_origin: bool,
+    self_signed: SelfSignedPolicy,
+    eviction: EvictionConfiguration,
+    bytes: usize,
+    // usage clock
+    tick: AtomicU64,
+}
+
+impl MemoryStore {
+    /// Instantiate empty store
+    pub fn new() -> Self {
+        Self {
+            certificates: BTreeMap::new(),
+            fingerprints: HashMap::new(),
+            serial: 0,
+            update_origin: false,
+            self_signed: SelfSignedPolicy::Exclude,
+            eviction: EvictionConfiguration::default(),
+            bytes: 0,
+            tick: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of stored certificates
+    pub fn len(&self) -> usize {
+        self.certificates.len()
+    }
+
+    /// Store is empty
+    pub fn is_empty(&self) -> bool {
+        self.certificates.is_empty()
+    }
+
+    /// Total size of stored DER-encoded certificates in bytes
+    pub fn size(&self) -> usize {
+        self.bytes
+    }
+
+    /// Iterate over stored certificates, in priority order
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<crate::Certificate>> + '_ {
+        self.certificates.values().map(|e| e.certificate.inner())
+    }
+
+    /// When `update` is `true`, a stored certificate downloaded again records the latest AIA URL as its origin.
+    /// Otherwise the first origin is kept. Priority is never changed, and certificates inserted directly stay pinned.
+    /// Defaults to `false`.
+    pub fn set_update_origin(&mut self, update: bool) {
+        self.update_origin = update;
+    }
+
+    /// Store self-signed certificates when `policy` is [`SelfSignedPolicy::Include`]. Defaults to
+    /// [`SelfSignedPolicy::Exclude`]. Stored self-signed certificates are kept when excluding again.
+    pub fn set_self_signed(&mut self, policy: SelfSignedPolicy) {
+        self.self_signed = policy;
+    }
+
+    /// Bound the store with `eviction`, evicting immediately if over the new bounds. Returns the evicted certificates,
+    /// in eviction order.
+    pub fn set_eviction(
+        &mut self,
+        eviction: EvictionConfiguration,
+    ) -> Vec<Arc<crate::Certificate>> {
+        self.eviction = eviction;
+        self.evict()
+    }
+
+    /// Evict expired certificates if configured, then evict certificates downloaded from AIA URLs until within bounds.
+    /// Runs on every insert, call to also evict certificates expiring while idle. Returns the evicted certificates, in
+    /// eviction order.
+    pub fn evict(&mut self) -> Vec<Arc<crate::Certificate>> {
+        self.evict_except(None)
+    }
+
+    // evict, sparing the certificate at priority `spared` unless nothing else is evictable
+    fn evict_except(&mut self, mut spared: Option<usize>) -> Vec<Arc<crate::Certificate>> {
+        let mut evicted = vec![];
+
+        if self.eviction.expired {
+            let now = SystemTime::now();
+            let expired = self
+                .certificates
+                .iter()
+                .filter(|(_, e)| e.expired(now))
+                .map(|(priority, _)| *priority)
+                .collect::<Vec<usize>>();
+            evicted.extend(expired.into_iter().filter_map(|p| self.remove_priority(p)));
+        }
+
+        while self.over_bounds() {
+            let candidate = self
+                .certificates
+                .iter()
+                .filter(|(priority, e)| !e.pinned && Some(**priority) != spared)
+                .min_by_key(|(priority, e)| {
+                    let used = e.used.load(Ordering::Relaxed);
+                    match self.eviction.policy {
+                        EvictionPolicy::Lru => (0, used, **priority),
+                        EvictionPolicy::Lfu => (e.hits.load(Ordering::Relaxed), used, **priority),
+                    }
+                })
+                .map(|(priority, _)| *priority)
+                .or_else(|| {
+                    spared
+                        .take()
+                        .filter(|p| self.certificates.get(p).is_some_and(|e| !e.pinned))
+                });
+
+            match candidate.and_then(|p| self.remove_priority(p)) {
+                Some(certificate) => evicted.push(certificate),
+                // only pinned certificates left
+                None => break,
+            }
+        }
+
+        evicted
+    }
+
+    /// Insert `certificate` with the lowest priority. Returns `false` if the certificate is already stored, or
+    /// self-signed and excluded. Inserted certificates are pinned, and never evicted to meet bounds.
+    pub fn insert<I: Into<Arc<crate::Certificate>>>(&mut self, certificate: I) -> bool {
+        self.insert_with_origin(certificate.into(), CertificateOrigin::Store, true)
+    }
+
+    /// Remove the certificate with SHA-256 `fingerprint`. See [`fingerprint`].
+    pub fn remove(&mut self, fingerprint: &[u8]) -> Option<Arc<crate::Certificate>> {
+        let priority = *self.fingerprints.get(fingerprint)?;
+        self.remove_priority(priority)
+    }
+
+    /// Remove every certificate matching `predicate`, returning the removed certificates in priority order
+    pub fn remove_where<P: FnMut(&crate::Certificate) -> bool>(
+        &mut self,
+        mut predicate: P,
+    ) -> Vec<Arc<crate::Certificate>> {
+        let matched = self
+            .certificates
+            .iter()
+            .filter(|(_, e)| predicate(e.certificate.inner()))
+            .map(|(priority, _)| *priority)
+            .collect::<Vec<usize>>();
+        matched
+            .into_iter()
+            .filter_map(|p| self.remove_priority(p))
+            .collect()
+    }
+
+    /// Certificate with SHA-256 `fingerprint`. See [`fingerprint`].
+    pub fn get(&self, fingerprint: &[u8]) -> Option<&Arc<crate::Certificate>> {
+        self.entry(fingerprint).map(|e| e.certificate.inner())
+    }
+
+    /// Origin of the certificate with SHA-256 `fingerprint`: [`CertificateOrigin::Store`] if inserted directly, or the
+    /// AIA URL it was downloaded from. See [`fingerprint`].
+    pub fn origin(&self, fingerprint: &[u8]) -> Option<&CertificateOrigin> {
+        self.entry(fingerprint).map(|e| e.certificate.origin())
+    }
+
+    /// Certificates with `subject`, in priority order
+    pub fn by_subject(&self, subject: &Name) -> Vec<&Arc<crate::Certificate>> {
+        self.iter()
+            .filter(|c| c.tbs_certificate.subject == *subject)
+            .collect()
+    }
+
+    /// Certificates with subject key identifier `key_identifier`, in priority order
+    pub fn by_subject_key_identifier(
+        &self,
+        key_identifier: &[u8],
+    ) -> Vec<&Arc<crate::Certificate>> {
+        self.iter()
+            .filter(|c| {
+                c.tbs_certificate
+                    .get::<SubjectKeyIdentifier>()
+                    .ok()
+                    .flatten()
+                    .is_some_and(|(_, ski)| ski.0.as_bytes() == key_identifier)
+            })
+            .collect()
+    }
+
+    /// Serialize stored certificates, in priority order with their origins and pins, to restore on another store. See
+    /// [`restore`](Self::restore). Configuration, such as eviction bounds, and usage are not included. Available with
+    /// the `persist` feature.
+    #[cfg(feature = "persist")]
+    pub fn snapshot(&self) -> MemoryStoreResult<Vec<u8>> {
+        let certificates = self
+            .certificates
+            .values()
+            .map(|e| {
+                Ok(SnapshotEntry {
+                    certificate: STANDARD.encode(e.certificate.inner().to_der()?),
+                    url: match e.certificate.origin() {
+                        CertificateOrigin::Url(url) => Some(url.to_string()),
+                        _ => None,
+                    },
+                    pinned: e.pinned,
+                })
+            })
+            .collect::<MemoryStoreResult<Vec<SnapshotEntry>>>()?;
+
+        Ok(serde_json::to_vec(&Snapshot {
+            version: SNAPSHOT_VERSION,
+            certificates,
+        })?)
+    }
+
+    /// Insert certificates from `snapshot` with the lowest priority, in snapshot order and keeping their origins and
+    /// pins, so certificates downloaded from AIA URLs remain evictable and inserted certificates stay pinned, even
+    /// when their origin was updated to a URL. Certificates already stored, or self-signed and
+    /// excluded, are skipped. Returns the number of certificates inserted. A malformed snapshot inserts nothing.
+    /// Available with the `persist` feature.
+    #[cfg(feature = "persist")]
+    pub fn restore(&mut self, snapshot: &[u8]) -> MemoryStoreResult<usize> {
+        let snapshot: Snapshot = serde_json::from_slice(snapshot)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(MemoryStoreError::Error(format!(
+                "unsupported snapshot version: {}",
+                snapshot.version
+            )));
+        }
+
+        let certificates = snapshot
+            .certificates
+            .into_iter()
+            .map(|e| {
+                let certificate =
+                    crate::Certificate::from_der(STANDARD.decode(e.certificate)?.as_slice())?;
+                let origin = match e.url {
+                    Some(url) => CertificateOrigin::Url(
+                        Url::parse(url.as_str())
+                            .map_err(|e| MemoryStoreError::Error(format!("invalid url: {}", e)))?,
+                    ),
+                    None => CertificateOrigin::Store,
+                };
+                Ok((Arc::new(certificate), origin, e.pinned))
+            })
+            .collect::<MemoryStoreResult<Vec<(Arc<crate::Certificate>, CertificateOrigin, bool)>>>(
+            )?;
+
+        let mut inserted = 0;
+        for (certificate, origin, pinned) in certificates {
+            if self.insert_with_origin(certificate, origin, pinned) {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
+    // insert certificates of `other` whose origin matches `predicate` with the lowest priority, in priority order and
+    // keeping their origins and pins. returns the number of certificates inserted
+    #[cfg(feature = "rustls")]
+    pub(crate) fn extend_from<P: Fn(&CertificateOrigin) -> bool>(
+        &mut self,
+        other: &MemoryStore,
+        predicate: P,
+    ) -> usize {
+        let mut inserted = 0;
+        for entry in other.certificates.values() {
+            let origin = entry.certificate.origin();
+            if predicate(origin)
+                && self.insert_with_origin(
+                    entry.certificate.inner().clone(),
+                    origin.clone(),
+                    entry.pinned,
+                )
+            {
+                inserted += 1;
+            }
+        }
+        inserted
+    }
+
+    fn entry(&self, fingerprint: &[u8]) -> Option<&Entry> {
+        self.fingerprints
+            .get(fingerprint)
+            .and_then(|priority| self.certificates.get(priority))
+    }
+
+    fn remove_priority(&mut self, priority: usize) -> Option<Arc<crate::Certificate>> {
+        let entry = self.certificates.remove(&priority)?;
+        self.fingerprints.remove(entry.certificate.fingerprint());
+        self.bytes -= entry.size;
+        Some(entry.certificate.inner().clone())
+    }
+
+    fn over_bounds(&self) -> bool {
+        (self.eviction.max_certificates != 0
+            && self.certificates.len() > self.eviction.max_certificates)
+            || (self.eviction.max_bytes != 0 && self.bytes > self.eviction.max_bytes)
+    }
+
+    fn insert_with_origin(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+        origin: CertificateOrigin,
+        pinned: bool,
+    ) -> bool {
+        // can't be fingerprinted
+        let Ok(mut certificate) = Certificate::try_from(certificate) else {
+            return false;
+        };
+        if self.self_signed == SelfSignedPolicy::Exclude && certificate.issued(&certificate) {
+            return false;
+        }
+
+        // exact duplicate
+        if let Some(priority) = self.fingerprints.get(certificate.fingerprint()) {
+            if self.update_origin && matches!(origin, CertificateOrigin::Url(_)) {
+                if let Some(stored) = self.certificates.get_mut(priority) {
+                    stored.certificate.set_origin(origin);
+                }
+            }
+            return false;
+        }
+
+        self.serial += 1;
+        certificate.set_origin(origin);
+        let size = certificate
+            .inner()
+            .encoded_len()
+            .map_or(0, |l| u32::from(l) as usize);
+        self.fingerprints
+            .insert(certificate.fingerprint().to_vec(), self.serial);
+        self.certificates.insert(
+            self.serial,
+            Entry {
+                certificate,
+                pinned,
+                size,
+                used: AtomicU64::new(self.tick.fetch_add(1, Ordering::Relaxed)),
+                hits: AtomicU64::new(0),
+            },
+        );
+        self.bytes += size;
+
+        // evicted anyway if expired, or if too large to fit beside pinned certificates
+        let serial = self.serial;
+        self.evict_except(Some(serial));
+        self.certificates.contains_key(&serial)
+    }
+}
+
+impl Clone for MemoryStore {
+    fn clone(&self) -> Self {
+        Self {
+            certificates: self.certificates.clone(),
+            fingerprints: self.fingerprints.clone(),
+            serial: self.serial,
+            update_origin: self.update_origin,
+            self_signed: self.self_signed,
+            eviction: self.eviction.clone(),
+            bytes: self.bytes,
+            tick: AtomicU64::new(self.tick.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[async_trait]
+impl CertificateBackend for MemoryStore {
+    type CertificateBackendError = Infallible;
+
+    async fn issuers(
+        &self,
+        certificate: &crate::Certificate,
+    ) -> Result<Vec<(Arc<crate::Certificate>, CertificateOrigin)>, Self::CertificateBackendError>
+    {
+        let issuer = certificate.tbs_certificate.issuer.to_string();
+        let now = SystemTime::now();
+        Ok(self
+            .certificates
+            .values()
+            .filter(|e| e.certificate.subject() == issuer)
+            .filter(|e| !(self.eviction.expired && e.expired(now)))
+            .map(|e| {
+                e.used
+                    .store(self.tick.fetch_add(1, Ordering::Relaxed), Ordering::Relaxed);
+                e.hits.fetch_add(1, Ordering::Relaxed);
+                (
+                    e.certificate.inner().clone(),
+                    e.certificate.origin().clone(),
+                )
+            })
+            .collect())
+    }
+
+    async fn insert(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        Ok(MemoryStore::insert(self, certificate))
+    }
+
+    async fn insert_from_url(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+        url: &Url,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        Ok(self.insert_with_origin(certificate, CertificateOrigin::Url(url.clone()), false))
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Into<Arc<crate::Certificate>>> FromIterator<C> for MemoryStore {
+    fn from_iter<T: IntoIterator<Item = C>>(iter: T) -> Self {
+        let mut store = Self::new();
+        for certificate in iter {
+            store.insert(certificate);
+        }
+        store
+    }
+}
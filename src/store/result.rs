@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::result;
+
+pub type MemoryStoreResult<T> = result::Result<T, MemoryStoreError>;
+
+/// Errors when snapshotting or restoring [`MemoryStore`](crate::store::MemoryStore)
+#[derive(Debug)]
+pub enum MemoryStoreError {
+    /// Errors when the snapshot is malformed
+    Error(String),
+    /// Errors when encoding or decoding certificates
+    DerError(der::Error),
+    /// Errors when encoding or decoding the snapshot
+    #[cfg(feature = "persist")]
+    JsonError(serde_json::Error),
+    /// Errors when decoding certificates from the snapshot
+    Base64Error(base64::DecodeError),
+}
+
+impl Display for MemoryStoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryStoreError::Error(e) => {
+                write!(f, "memory store -> error: {}", e)
+            }
+            MemoryStoreError::DerError(e) => {
+                write!(f, "memory store -> der error: {}", e)
+            }
+            #[cfg(feature = "persist")]
+            MemoryStoreError::JsonError(e) => {
+                write!(f, "memory store -> json error: {}", e)
+            }
+            MemoryStoreError::Base64Error(e) => {
+                write!(f, "memory store -> base64 error: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for MemoryStoreError {}
+
+impl From<der::Error> for MemoryStoreError {
+    fn from(e: der::Error) -> Self {
+        Self::DerError(e)
+    }
+}
+
+#[cfg(feature = "persist")]
+impl From<serde_json::Error> for MemoryStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::JsonError(e)
+    }
+}
+
+impl From<base64::DecodeError> for MemoryStoreError {
+    fn from(e: base64::DecodeError) -> Self {
+        Self::Base64Error(e)
+    }
+}
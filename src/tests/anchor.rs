@@ -0,0 +1,68 @@
+use crate::anchor::TrustAnchor;
+use crate::certificate::Certificate;
+use der::oid::AssociatedOid;
+use std::sync::Arc;
+use x509_cert::ext::pkix::AuthorityKeyIdentifier;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+#[test]
+fn test_issued() {
+    let mut certificates = CertificatePathGenerator::generate(3, "authority1").unwrap();
+    let root = certificates.pop().unwrap();
+    let ic: Certificate = Arc::new(certificates.pop().unwrap()).try_into().unwrap();
+    let ee: Certificate = Arc::new(certificates.pop().unwrap()).try_into().unwrap();
+
+    let anchor = TrustAnchor::try_from(&root).unwrap();
+    assert!(anchor.issued(&ic));
+    assert!(!anchor.issued(&ee));
+
+    let anchor = TrustAnchor::new(
+        &root.tbs_certificate.subject,
+        &root.tbs_certificate.subject_public_key_info,
+    )
+    .unwrap();
+    assert!(anchor.issued(&ic));
+}
+
+#[test]
+fn test_issued_without_key_identifier() {
+    let (certificates, keys) =
+        CertificatePathGenerator::generate_with_keys(3, "authority2").unwrap();
+    let root = &certificates[2];
+    let ic: Certificate = Arc::new(
+        CertificatePathGenerator::without_extension(
+            &certificates[1],
+            &keys[2],
+            AuthorityKeyIdentifier::OID,
+        )
+        .unwrap(),
+    )
+    .try_into()
+    .unwrap();
+    assert!(ic.authority_key_identifier().is_none());
+    assert!(TrustAnchor::try_from(root).unwrap().issued(&ic));
+
+    // same subject, different key
+    let other = CertificatePathGenerator::generate(3, "authority2")
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(root.tbs_certificate.subject, other.tbs_certificate.subject);
+    assert!(!TrustAnchor::try_from(&other).unwrap().issued(&ic));
+}
+
+#[test]
+fn test_spki() {
+    let root1 = CertificatePathGenerator::generate(2, "authority")
+        .unwrap()
+        .pop()
+        .unwrap();
+    let mut certificates = CertificatePathGenerator::generate(2, "authority").unwrap();
+    let root2 = certificates.pop().unwrap();
+    let ee: Certificate = Arc::new(certificates.pop().unwrap()).try_into().unwrap();
+
+    // same subject, different key
+    assert_eq!(root1.tbs_certificate.subject, root2.tbs_certificate.subject);
+    assert!(!TrustAnchor::try_from(&root1).unwrap().issued(&ee));
+    assert!(TrustAnchor::try_from(&root2).unwrap().issued(&ee));
+}
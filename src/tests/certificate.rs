@@ -1,4 +1,5 @@
 use crate::certificate::Certificate;
+use std::collections::HashSet;
 use std::sync::Arc;
 use url::Url;
 use x509_path_finder_material::generate::CertificatePathGenerator;
@@ -8,7 +9,7 @@ fn test_issuers() {
     let certificates = CertificatePathGenerator::generate(2, "issuers")
         .unwrap()
         .into_iter()
-        .map(|c| Arc::new(c).into())
+        .map(|c| Arc::new(c).try_into().unwrap())
         .collect::<Vec<Certificate>>();
 
     assert!(certificates[1].issued(&certificates[0]));
@@ -19,7 +20,7 @@ fn test_aia() {
     let certificates = CertificatePathGenerator::generate(2, "aia")
         .unwrap()
         .into_iter()
-        .map(|c| Arc::new(c).into())
+        .map(|c| Arc::new(c).try_into().unwrap())
         .collect::<Vec<Certificate>>();
 
     assert_eq!(
@@ -27,3 +28,19 @@ fn test_aia() {
         certificates[0].aia()
     )
 }
+
+#[test]
+fn test_identity() {
+    let certificates = CertificatePathGenerator::generate(2, "identity").unwrap();
+    let certificate = Certificate::try_from(Arc::new(certificates[0].clone())).unwrap();
+    let copy = Certificate::try_from(Arc::new(certificates[0].clone())).unwrap();
+    let other = Certificate::try_from(Arc::new(certificates[1].clone())).unwrap();
+
+    assert_eq!(
+        crate::store::fingerprint(&certificates[0]).unwrap(),
+        certificate.fingerprint()
+    );
+    assert_eq!(certificate, copy);
+    assert_ne!(certificate, other);
+    assert_eq!(2, HashSet::from([certificate, copy, other]).len());
+}
@@ -0,0 +1,28 @@
+use crate::certificate::Certificate;
+use crate::edge::{Edge, Edges};
+use std::sync::Arc;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+#[test]
+fn test_start() {
+    let certificates = CertificatePathGenerator::generate(3, "edges")
+        .unwrap()
+        .into_iter()
+        .map(|c| Arc::new(c).try_into().unwrap())
+        .collect::<Vec<Certificate>>();
+
+    let mut edges = Edges::new();
+    edges.start(certificates[0].clone());
+    let target = edges.next().unwrap();
+    edges.visit(target.clone());
+    edges.extend(
+        target,
+        vec![Edge::Certificate(Arc::new(certificates[1].clone()))],
+    );
+
+    // search state of previous search forgotten
+    edges.start(certificates[0].clone());
+    let target = edges.next().unwrap();
+    assert!(!edges.visited(&target));
+    assert!(edges.next().is_none());
+}
@@ -1,7 +1,16 @@
+use crate::anchor::TrustAnchor;
+use crate::api::{CertificateBackend, CertificatePathValidation, PathValidator};
+use crate::provided::validator::default::result::DefaultPathValidatorError;
 use crate::report::CertificateOrigin;
+use crate::store::conformance;
+#[cfg(feature = "persist")]
+use crate::store::disk::DiskStore;
+use crate::store::SelfSignedPolicy;
 use crate::tests::test_validator::TestPathValidator;
-use crate::{TestAIA, X509PathFinder, X509PathFinderConfiguration};
+use crate::{TestAIA, X509PathFinder, X509PathFinderConfiguration, X509PathFinderError};
+use async_trait::async_trait;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
@@ -29,6 +38,9 @@ async fn test_limit() {
         }),
         validator: validator.clone(),
         certificates: vec![ee.clone().into()],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     let report = search.find(ee.clone()).await.unwrap();
@@ -55,11 +67,63 @@ async fn test_limit() {
         }),
         validator: validator.clone(),
         certificates: vec![ee.clone().into()],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     assert!(search.find(ee.clone()).await.is_err());
 }
 
+#[tokio::test]
+async fn test_anchor() {
+    let mut certificates = CertificatePathGenerator::generate(4, "authority")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let root = certificates.pop().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    let validator = TestPathValidator::new(vec![root.as_ref().clone()]);
+
+    // top certificate has aia url, fetching it exceeds limit
+    let aia = TestAIA {
+        certificates: HashMap::new(),
+        sleep: Some(Duration::from_millis(200)),
+    };
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::from_millis(100),
+        aia: Some(aia.clone()),
+        validator: validator.clone(),
+        certificates: certificates.clone(),
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+
+    assert!(search.find(ee.clone()).await.is_err());
+
+    // issued by anchor, path is validated before fetching
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::from_millis(100),
+        aia: Some(aia),
+        validator,
+        certificates,
+        anchors: vec![TrustAnchor::try_from(root.as_ref()).unwrap()],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+
+    let report = search.find(ee).await.unwrap();
+    let found = report.found.unwrap();
+
+    assert_eq!(0, report.failures.len());
+    assert_eq!(expected, found.path);
+}
+
 #[tokio::test]
 async fn test_self_signed() {
     let root = CertificatePathGenerator::generate(1, "0")
@@ -75,6 +139,9 @@ async fn test_self_signed() {
         aia: None,
         validator,
         certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     let report = search.find(root.clone()).await.unwrap();
@@ -90,7 +157,7 @@ async fn test_direct_path_no_aia() {
     let mut certificates = CertificatePathGenerator::generate(8, "0")
         .unwrap()
         .into_iter()
-        .map(|c| Arc::new(c))
+        .map(Arc::new)
         .collect::<Vec<Arc<crate::Certificate>>>();
     let root = certificates.pop().unwrap();
     let expected = certificates.clone();
@@ -104,6 +171,9 @@ async fn test_direct_path_no_aia() {
         aia: None,
         validator,
         certificates,
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     let report = search.find(ee).await.unwrap();
@@ -158,6 +228,9 @@ async fn test_cross_first_no_aia() {
         aia: None,
         validator,
         certificates: cached_certificates_cross_first,
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     })
     .find(authority1_ee.clone())
     .await
@@ -203,6 +276,9 @@ async fn test_cross_last_no_aia() {
         aia: None,
         validator,
         certificates: cached_certificates_cross_last,
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     })
     .find(authority1_ee.clone())
     .await
@@ -256,6 +332,9 @@ async fn test_cross_first_dead_end_no_aia() {
         aia: None,
         validator,
         certificates: cached_certificates_cross_first,
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     })
     .find(authority1_ee.clone())
     .await
@@ -279,6 +358,64 @@ async fn test_cross_first_dead_end_no_aia() {
     );
 }
 
+#[tokio::test]
+async fn test_cross_first_vetoed_no_aia() {
+    let mut authority1_certificates = CertificatePathGenerator::generate(8, "authority1")
+        .unwrap()
+        .into_iter()
+        .map(|c| c.into())
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let authority1_root = authority1_certificates.pop().unwrap();
+    let authority1_ee = authority1_certificates[0].clone();
+    let authority1_ic = authority1_certificates[1].clone();
+
+    let (mut authority2_certificates, mut authority2_keys) =
+        CertificatePathGenerator::generate_with_keys(1, "authority2").unwrap();
+    let authority2_root = authority2_certificates.pop().unwrap();
+    let authority2_root_key = authority2_keys.pop().unwrap();
+
+    let cross =
+        CertificatePathGenerator::cross(&authority2_root, &authority2_root_key, &authority1_ic)
+            .unwrap();
+
+    let mut cached_certificates_cross_first = vec![Arc::new(cross.clone())];
+    cached_certificates_cross_first.extend(authority1_certificates.clone());
+
+    let validator = TestPathValidator::new_with_vetoes(
+        vec![authority1_root.as_ref().clone(), authority2_root],
+        vec![cross.clone()],
+    );
+
+    let report = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: None,
+        validator,
+        certificates: cached_certificates_cross_first,
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    })
+    .find(authority1_ee.clone())
+    .await
+    .unwrap();
+
+    let found = report.found.unwrap();
+
+    assert_eq!(0, report.failures.len());
+    assert_eq!(authority1_certificates, found.path);
+
+    assert_eq!(1, report.vetoes.len());
+    assert_eq!(
+        vec![authority1_ee.clone(), Arc::new(cross)],
+        report.vetoes[0].path
+    );
+    assert_eq!(
+        vec![CertificateOrigin::Target, CertificateOrigin::Store],
+        report.vetoes[0].origin
+    );
+    assert_eq!("issuer vetoed", report.vetoes[0].reason);
+}
+
 #[tokio::test]
 async fn test_direct_path_only_aia() {
     let mut certificates = CertificatePathGenerator::generate(8, "authority").unwrap();
@@ -286,7 +423,7 @@ async fn test_direct_path_only_aia() {
     let expected = certificates
         .clone()
         .into_iter()
-        .map(|c| Arc::new(c))
+        .map(Arc::new)
         .collect::<Vec<Arc<crate::Certificate>>>();
     let ee = certificates.remove(0);
 
@@ -315,6 +452,9 @@ async fn test_direct_path_only_aia() {
         }),
         validator,
         certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     let report = search.find(ee).await.unwrap();
@@ -372,6 +512,9 @@ async fn test_direct_path_partial_aia() {
         }),
         validator,
         certificates: vec![certificates[2].clone(), certificates[4].clone()],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     let report = search.find(ee).await.unwrap();
@@ -460,6 +603,9 @@ async fn escape_path_loop() {
         aia: None,
         validator: validator.clone(),
         certificates: vec![a.clone().into(), b.clone().into()],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     search.find(target.clone()).await.unwrap().found.unwrap();
@@ -469,7 +615,624 @@ async fn escape_path_loop() {
         aia: None,
         validator,
         certificates: vec![z.into(), y.into(), a.into(), b.into()],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
     });
 
     search.find(target).await.unwrap().found.unwrap();
 }
+
+#[tokio::test]
+async fn test_horizon() {
+    let (certificates, keys) =
+        CertificatePathGenerator::generate_with_keys(3, "authority").unwrap();
+    let day = Duration::from_secs(86400);
+    let ee = CertificatePathGenerator::with_validity(&certificates[0], &keys[1], day * 90).unwrap();
+    let short = Arc::new(certificates[1].clone());
+    let long = Arc::new(
+        CertificatePathGenerator::with_validity(&certificates[1], &keys[2], day * 60).unwrap(),
+    );
+
+    let search = |horizon| {
+        X509PathFinder::new(X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: None,
+            validator: TestPathValidator::new(vec![certificates[2].clone()]),
+            certificates: vec![short.clone(), long.clone()],
+            anchors: vec![],
+            horizon,
+            self_signed: SelfSignedPolicy::Exclude,
+        })
+    };
+
+    // first valid path found, reporting its earliest expiry
+    let report = search(Duration::ZERO).find(ee.clone()).await.unwrap();
+    let found = report.found.unwrap();
+    assert_eq!(short, found.path[1]);
+    assert_eq!(1, found.expiry.certificate);
+    assert_eq!(
+        short.tbs_certificate.validity.not_after.to_system_time(),
+        found.expiry.not_after
+    );
+    assert!(!found.expiry.within_horizon);
+    assert!(report.expiring.is_empty());
+
+    // path expiring within horizon passed over for longer lived path
+    let report = search(day * 30).find(ee.clone()).await.unwrap();
+    let found = report.found.unwrap();
+    assert_eq!(long, found.path[1]);
+    assert!(!found.expiry.within_horizon);
+    assert_eq!(1, report.expiring.len());
+    assert_eq!(short, report.expiring[0].path[1]);
+    assert_eq!(
+        format!(
+            "valid but expiring within 30 days: {}",
+            short.tbs_certificate.subject
+        ),
+        report.expiring[0].reason
+    );
+
+    // no path outlives horizon, first valid path found
+    let report = search(day * 120).find(ee).await.unwrap();
+    let found = report.found.unwrap();
+    assert_eq!(short, found.path[1]);
+    assert!(found.expiry.within_horizon);
+    assert_eq!(1, report.expiring.len());
+    assert_eq!(long, report.expiring[0].path[1]);
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn test_find_blocking() {
+    let mut certificates = CertificatePathGenerator::generate(4, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let expected = certificates
+        .clone()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let ee = certificates.remove(0);
+
+    // no aia
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: None,
+        validator: TestPathValidator::new(vec![root.clone()]),
+        certificates: certificates.iter().cloned().map(Arc::new).collect(),
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+    assert_eq!(
+        expected,
+        search
+            .find_blocking(ee.clone())
+            .unwrap()
+            .found
+            .unwrap()
+            .path
+    );
+
+    // aia, searched on a private runtime
+    let aia = HashMap::from_iter([
+        (
+            Url::parse("test://2.authority").unwrap(),
+            Arc::new(certificates[0].clone()),
+        ),
+        (
+            Url::parse("test://1.authority").unwrap(),
+            Arc::new(certificates[1].clone()),
+        ),
+    ]);
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: Some(TestAIA {
+            certificates: aia,
+            sleep: None,
+        }),
+        validator: TestPathValidator::new(vec![root]),
+        certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+    assert_eq!(
+        expected,
+        search.find_blocking(ee).unwrap().found.unwrap().path
+    );
+}
+
+#[cfg(feature = "blocking")]
+#[tokio::test]
+async fn test_find_blocking_within_runtime() {
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let search = |aia| {
+        X509PathFinder::new(X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia,
+            validator: TestPathValidator::new(vec![root.clone()]),
+            certificates: certificates.iter().cloned().map(Arc::new).collect(),
+            anchors: vec![],
+            horizon: Duration::default(),
+            self_signed: SelfSignedPolicy::Exclude,
+        })
+    };
+
+    // private runtime can't be started within a runtime, with or without aia
+    assert!(search(None).find_blocking(ee.clone()).is_err());
+    assert!(search(Some(TestAIA {
+        certificates: HashMap::new(),
+        sleep: None,
+    }))
+    .find_blocking(ee)
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_store_between_searches() {
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: None,
+        validator: TestPathValidator::new(vec![root]),
+        certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+    assert!(search.find(ee.clone()).await.unwrap().found.is_none());
+
+    // intermediate learned later
+    assert!(search.store_mut().insert(certificates[0].clone()));
+    assert_eq!(1, search.store().len());
+    assert!(search.find(ee.clone()).await.unwrap().found.is_some());
+
+    // intermediate removed
+    let fingerprint = crate::store::fingerprint(&certificates[0]).unwrap();
+    assert!(search.store_mut().remove(fingerprint.as_slice()).is_some());
+    assert!(search.find(ee).await.unwrap().found.is_none());
+}
+
+// naive store backend
+#[derive(Default)]
+struct VecStore(Vec<Arc<crate::Certificate>>);
+
+#[async_trait]
+impl CertificateBackend for VecStore {
+    type CertificateBackendError = Infallible;
+
+    async fn issuers(
+        &self,
+        certificate: &crate::Certificate,
+    ) -> Result<Vec<(Arc<crate::Certificate>, CertificateOrigin)>, Self::CertificateBackendError>
+    {
+        Ok(self
+            .0
+            .iter()
+            .filter(|c| c.tbs_certificate.subject == certificate.tbs_certificate.issuer)
+            .map(|c| (c.clone(), CertificateOrigin::Store))
+            .collect())
+    }
+
+    async fn insert(
+        &mut self,
+        certificate: Arc<crate::Certificate>,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        if certificate.tbs_certificate.subject == certificate.tbs_certificate.issuer
+            || self.0.contains(&certificate)
+        {
+            return Ok(false);
+        }
+        self.0.push(certificate);
+        Ok(true)
+    }
+}
+
+#[tokio::test]
+async fn test_with_store() {
+    conformance::run(VecStore::default).await;
+
+    let mut certificates = CertificatePathGenerator::generate(4, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let mut aia = HashMap::new();
+    aia.insert(
+        Url::parse("test://1.authority").unwrap(),
+        Arc::new(certificates[1].clone()),
+    );
+
+    let mut search = X509PathFinder::with_store(
+        X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: Some(TestAIA {
+                certificates: aia,
+                sleep: None,
+            }),
+            validator: TestPathValidator::new(vec![root]),
+            certificates: vec![Arc::new(certificates[0].clone())],
+            anchors: vec![],
+            horizon: Duration::default(),
+            self_signed: SelfSignedPolicy::Exclude,
+        },
+        VecStore::default(),
+    );
+
+    let found = search.find(ee).await.unwrap().found.unwrap();
+    assert_eq!(
+        vec![
+            CertificateOrigin::Target,
+            CertificateOrigin::Store,
+            CertificateOrigin::Url(Url::parse("test://1.authority").unwrap()),
+        ],
+        found.origin
+    );
+    assert_eq!(
+        found
+            .path
+            .iter()
+            .map(|c| crate::store::fingerprint(c).unwrap())
+            .collect::<Vec<Vec<u8>>>(),
+        found.fingerprints
+    );
+
+    // configured and downloaded certificates inserted into store
+    assert_eq!(
+        certificates
+            .into_iter()
+            .map(Arc::new)
+            .collect::<Vec<Arc<crate::Certificate>>>(),
+        search.store().0
+    );
+}
+
+// store backend refusing every insert
+struct ReadOnlyStore(VecStore);
+
+#[derive(Debug)]
+struct ReadOnlyStoreError;
+
+impl std::fmt::Display for ReadOnlyStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "read-only store")
+    }
+}
+
+impl crate::api::CertificateBackendError for ReadOnlyStoreError {}
+
+impl From<ReadOnlyStoreError> for X509PathFinderError {
+    fn from(e: ReadOnlyStoreError) -> Self {
+        Self::CertificateBackendError(Box::new(e))
+    }
+}
+
+#[async_trait]
+impl CertificateBackend for ReadOnlyStore {
+    type CertificateBackendError = ReadOnlyStoreError;
+
+    async fn issuers(
+        &self,
+        certificate: &crate::Certificate,
+    ) -> Result<Vec<(Arc<crate::Certificate>, CertificateOrigin)>, Self::CertificateBackendError>
+    {
+        Ok(self.0.issuers(certificate).await.unwrap())
+    }
+
+    async fn insert(
+        &mut self,
+        _certificate: Arc<crate::Certificate>,
+    ) -> Result<bool, Self::CertificateBackendError> {
+        Err(ReadOnlyStoreError)
+    }
+}
+
+#[tokio::test]
+async fn test_store_insert_error() {
+    let mut certificates = CertificatePathGenerator::generate(4, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let mut aia = HashMap::new();
+    aia.insert(
+        Url::parse("test://1.authority").unwrap(),
+        Arc::new(certificates[1].clone()),
+    );
+
+    let mut search = X509PathFinder::with_store(
+        X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: Some(TestAIA {
+                certificates: aia,
+                sleep: None,
+            }),
+            validator: TestPathValidator::new(vec![root]),
+            certificates: vec![],
+            anchors: vec![],
+            horizon: Duration::default(),
+            self_signed: SelfSignedPolicy::Exclude,
+        },
+        ReadOnlyStore(VecStore(vec![Arc::new(certificates[0].clone())])),
+    );
+
+    // downloaded certificate not stored, path still found through it
+    let found = search.find(ee).await.unwrap().found.unwrap();
+    assert_eq!(
+        vec![
+            CertificateOrigin::Target,
+            CertificateOrigin::Store,
+            CertificateOrigin::Url(Url::parse("test://1.authority").unwrap()),
+        ],
+        found.origin
+    );
+    assert_eq!(1, search.store().0 .0.len());
+}
+
+#[cfg(feature = "persist")]
+#[tokio::test]
+async fn test_disk_store() {
+    let dir =
+        std::env::temp_dir().join(format!("x509-path-finder-find-disk-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+    let url = Url::parse("test://1.authority").unwrap();
+
+    let mut aia = HashMap::new();
+    aia.insert(url.clone(), Arc::new(certificates[0].clone()));
+
+    let config = |aia| X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia,
+        validator: TestPathValidator::new(vec![root.clone()]),
+        certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    };
+
+    let mut search = X509PathFinder::with_store(
+        config(Some(TestAIA {
+            certificates: aia,
+            sleep: None,
+        })),
+        DiskStore::open(&dir).unwrap(),
+    );
+    assert!(search.find(ee.clone()).await.unwrap().found.is_some());
+    let fingerprint = crate::store::fingerprint(&certificates[0]).unwrap();
+    assert_eq!(
+        Some(url.clone()),
+        search.store().get(fingerprint.as_slice()).unwrap().url
+    );
+
+    // downloaded intermediate persisted, found without aia and reported with its url
+    let mut search = X509PathFinder::with_store(config(None), DiskStore::open(&dir).unwrap());
+    let found = search.find(ee).await.unwrap().found.unwrap();
+    assert_eq!(CertificateOrigin::Url(url), found.origin[1]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "persist")]
+#[tokio::test]
+async fn test_pending_retried() {
+    let dir = std::env::temp_dir().join(format!(
+        "x509-path-finder-find-pending-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let mut search = X509PathFinder::with_store(
+        X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: None,
+            validator: TestPathValidator::new(vec![root]),
+            certificates: vec![Arc::new(certificates[0].clone())],
+            anchors: vec![],
+            horizon: Duration::default(),
+            self_signed: SelfSignedPolicy::Exclude,
+        },
+        DiskStore::open(&dir).unwrap(),
+    );
+
+    // configured certificates can't be persisted, kept for the next search
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(search.find(ee.clone()).await.is_err());
+
+    std::fs::create_dir_all(&dir).unwrap();
+    assert!(search.find(ee).await.unwrap().found.is_some());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_self_signed_policy() {
+    let certificates = CertificatePathGenerator::generate(4, "authority")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let root = certificates[3].clone();
+    let ee = certificates[0].clone();
+
+    let search = |self_signed| {
+        X509PathFinder::new(X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: None,
+            validator: TestPathValidator::new(vec![root.as_ref().clone()]),
+            certificates: certificates[1..].to_vec(),
+            anchors: vec![TrustAnchor::try_from(root.as_ref()).unwrap()],
+            horizon: Duration::default(),
+            self_signed,
+        })
+    };
+
+    // root excluded by default
+    let mut excluded = search(SelfSignedPolicy::Exclude);
+    assert_eq!(2, excluded.store().len());
+    let report = excluded.find(ee.clone()).await.unwrap();
+    assert_eq!(0, report.failures.len());
+    assert_eq!(certificates[..3].to_vec(), report.found.unwrap().path);
+
+    // root included as final element, path not validated without it first
+    let mut included = search(SelfSignedPolicy::Include);
+    assert_eq!(3, included.store().len());
+    let report = included.find(ee.clone()).await.unwrap();
+    let found = report.found.unwrap();
+    assert_eq!(0, report.failures.len());
+    assert_eq!(certificates, found.path);
+    assert_eq!(
+        vec![
+            CertificateOrigin::Target,
+            CertificateOrigin::Store,
+            CertificateOrigin::Store,
+            CertificateOrigin::Store,
+        ],
+        found.origin
+    );
+    assert_eq!(
+        crate::store::fingerprint(&root).unwrap(),
+        found.fingerprints[3]
+    );
+
+    // path through root rejected, path ending at anchor still validated
+    let mut rejecting = X509PathFinder::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: None,
+        validator: RootRejectingValidator(TestPathValidator::new(vec![root.as_ref().clone()])),
+        certificates: certificates[1..].to_vec(),
+        anchors: vec![TrustAnchor::try_from(root.as_ref()).unwrap()],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Include,
+    });
+    let report = rejecting.find(ee).await.unwrap();
+    assert_eq!(1, report.failures.len());
+    assert_eq!(certificates, report.failures[0].path);
+    assert_eq!(certificates[..3].to_vec(), report.found.unwrap().path);
+}
+
+// rejects paths ending with a self-signed certificate
+struct RootRejectingValidator(TestPathValidator);
+
+impl PathValidator for RootRejectingValidator {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&crate::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        match path.last() {
+            Some(c) if c.tbs_certificate.subject == c.tbs_certificate.issuer => Ok(
+                CertificatePathValidation::NotFound("root in path".to_string()),
+            ),
+            _ => self.0.validate(path),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_self_signed_policy_aia() {
+    let certificates = CertificatePathGenerator::generate(4, "authority")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let root = certificates[3].clone();
+    let ee = certificates[0].clone();
+
+    // root published at top intermediate's aia url
+    let url = crate::certificate::Certificate::try_from(certificates[2].clone())
+        .unwrap()
+        .aia()[0]
+        .clone();
+    let aia = TestAIA {
+        certificates: HashMap::from([(url.clone(), root.clone())]),
+        sleep: None,
+    };
+
+    let search = |self_signed| {
+        X509PathFinder::new(X509PathFinderConfiguration {
+            limit: Duration::default(),
+            aia: Some(aia.clone()),
+            validator: TestPathValidator::new(vec![root.as_ref().clone()]),
+            certificates: certificates[1..3].to_vec(),
+            anchors: vec![],
+            horizon: Duration::default(),
+            self_signed,
+        })
+    };
+
+    // downloaded root neither stored nor included by default
+    let mut excluded = search(SelfSignedPolicy::Exclude);
+    let found = excluded.find(ee.clone()).await.unwrap().found.unwrap();
+    assert_eq!(certificates[..3].to_vec(), found.path);
+    assert_eq!(2, excluded.store().len());
+
+    // downloaded root stored and included
+    let mut included = search(SelfSignedPolicy::Include);
+    let found = included.find(ee.clone()).await.unwrap().found.unwrap();
+    assert_eq!(certificates, found.path);
+    assert_eq!(CertificateOrigin::Url(url.clone()), found.origin[3]);
+    let fingerprint = crate::store::fingerprint(&root).unwrap();
+    assert_eq!(
+        Some(&CertificateOrigin::Url(url)),
+        included.store().origin(fingerprint.as_slice())
+    );
+
+    // cached root found again from store
+    let found = included.find(ee).await.unwrap().found.unwrap();
+    assert_eq!(certificates, found.path);
+}
+
+#[cfg(feature = "persist")]
+#[tokio::test]
+async fn test_snapshot() {
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+    let url = Url::parse("test://1.authority").unwrap();
+
+    let mut aia = HashMap::new();
+    aia.insert(url.clone(), Arc::new(certificates[0].clone()));
+
+    let config = |aia| X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia,
+        validator: TestPathValidator::new(vec![root.clone()]),
+        certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    };
+
+    let mut search = X509PathFinder::new(config(Some(TestAIA {
+        certificates: aia,
+        sleep: None,
+    })));
+    assert!(search.find(ee.clone()).await.unwrap().found.is_some());
+    let snapshot = search.store().snapshot().unwrap();
+
+    // warmed from snapshot, found without aia
+    let mut search = X509PathFinder::new(config(None));
+    assert!(search.find(ee.clone()).await.unwrap().found.is_none());
+    assert_eq!(1, search.store_mut().restore(snapshot.as_slice()).unwrap());
+    let found = search.find(ee).await.unwrap().found.unwrap();
+    assert_eq!(CertificateOrigin::Url(url.clone()), found.origin[1]);
+    let fingerprint = crate::store::fingerprint(&certificates[0]).unwrap();
+    assert_eq!(
+        Some(&CertificateOrigin::Url(url)),
+        search.store().origin(fingerprint.as_slice())
+    );
+}
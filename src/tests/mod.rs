@@ -1,4 +1,8 @@
+mod anchor;
 mod certificate;
+mod edge;
 mod find;
 mod store;
 mod test_validator;
+#[cfg(feature = "rustls")]
+mod verifier;
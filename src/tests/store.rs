@@ -1,6 +1,14 @@
-use crate::certificate::Certificate;
-use crate::store::CertificateStore;
+use crate::api::CertificateBackend;
+use crate::report::CertificateOrigin;
+use crate::store::{
+    conformance, fingerprint, EvictionConfiguration, EvictionPolicy, MemoryStore, SelfSignedPolicy,
+};
+use der::Encode;
 use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::time::Time;
 use x509_path_finder_material::generate::CertificatePathGenerator;
 
 #[test]
@@ -8,45 +16,471 @@ fn test_ord() {
     let mut certificates = CertificatePathGenerator::generate(10, "issuers")
         .unwrap()
         .into_iter()
-        .map(|c| Arc::new(c).into())
-        .collect::<Vec<Certificate>>();
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
     certificates.pop().unwrap();
 
-    let expected = certificates
-        .clone()
+    // exact duplicates ignored, keeping first priority
+    let mut duplicated = certificates.clone();
+    duplicated.push(Arc::new(certificates[0].as_ref().clone()));
+    let mut store = MemoryStore::from_iter(duplicated);
+    assert!(!store.insert(certificates[0].clone()));
+    assert!(!store.insert(certificates[0].clone()));
+    assert!(!store.insert(certificates[1].clone()));
+
+    assert_eq!(
+        certificates,
+        store
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
+    );
+    assert_eq!(certificates.len(), store.len());
+}
+
+#[tokio::test]
+async fn test_issuer() {
+    let mut certificates = CertificatePathGenerator::generate(3, "issuers")
+        .unwrap()
         .into_iter()
-        .map(|c| c.into())
-        .collect::<Vec<Arc<Certificate>>>();
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    certificates.pop().unwrap();
+
+    let store = MemoryStore::from_iter(certificates.clone());
+    assert_eq!(
+        vec![(certificates[1].clone(), CertificateOrigin::Store)],
+        store.issuers(&certificates[0]).await.unwrap()
+    );
+
+    assert!(store.issuers(&certificates[1]).await.unwrap().is_empty());
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut certificates = CertificatePathGenerator::generate(4, "issuers").unwrap();
+    let root = certificates.pop().unwrap();
+
+    let mut store = MemoryStore::new();
+    assert!(store.is_empty());
 
-    let mut store = CertificateStore::from_iter(certificates.clone());
-    store.insert(certificates[0].clone());
-    store.insert(certificates[0].clone());
-    store.insert(certificates[0].clone());
-    store.insert(certificates[0].clone());
-    store.insert(certificates[1].clone());
-    store.insert(certificates[1].clone());
-    store.insert(certificates[1].clone());
+    // self-signed not stored by default
+    assert!(!store.insert(root));
+    for certificate in certificates.iter() {
+        assert!(store.insert(certificate.clone()));
+    }
+    assert_eq!(3, store.len());
 
+    // lowest priority
+    assert_eq!(&certificates[2], store.iter().last().unwrap().as_ref());
+
+    let fingerprint = fingerprint(&certificates[1]).unwrap();
     assert_eq!(
-        expected,
-        store.into_iter().collect::<Vec<Arc<Certificate>>>()
+        &certificates[1],
+        store.get(fingerprint.as_slice()).unwrap().as_ref()
     );
+    assert_eq!(
+        &certificates[1],
+        store.remove(fingerprint.as_slice()).unwrap().as_ref()
+    );
+    assert!(store.get(fingerprint.as_slice()).is_none());
+    assert!(store.remove(fingerprint.as_slice()).is_none());
+
+    let subject = certificates[2].tbs_certificate.subject.clone();
+    let removed = store.remove_where(|c| c.tbs_certificate.subject == subject);
+    assert_eq!(vec![Arc::new(certificates[2].clone())], removed);
+    assert_eq!(1, store.len());
 }
 
 #[test]
-fn test_issuer() {
+fn test_lookup() {
+    let mut certificates = CertificatePathGenerator::generate(3, "issuers").unwrap();
+    certificates.pop().unwrap();
+    let store = MemoryStore::from_iter(certificates.clone());
+
+    assert_eq!(
+        vec![&Arc::new(certificates[1].clone())],
+        store.by_subject(&certificates[1].tbs_certificate.subject)
+    );
+
+    let (_, ski) = certificates[1]
+        .tbs_certificate
+        .get::<SubjectKeyIdentifier>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        vec![&Arc::new(certificates[1].clone())],
+        store.by_subject_key_identifier(ski.0.as_bytes())
+    );
+    assert!(store.by_subject_key_identifier(&[0]).is_empty());
+}
+
+#[tokio::test]
+async fn test_conformance() {
+    conformance::run(MemoryStore::new).await;
+}
+
+#[tokio::test]
+async fn test_origin() {
     let mut certificates = CertificatePathGenerator::generate(3, "issuers")
         .unwrap()
         .into_iter()
-        .map(|c| Arc::new(c).into())
-        .collect::<Vec<Certificate>>();
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
     certificates.pop().unwrap();
+    let fingerprint = fingerprint(&certificates[1]).unwrap();
+    let first = Url::parse("test://first").unwrap();
+    let second = Url::parse("test://second").unwrap();
+    let third = Url::parse("test://third").unwrap();
+
+    let mut store = MemoryStore::from_iter(vec![certificates[0].clone()]);
+    assert!(store
+        .insert_from_url(certificates[1].clone(), &first)
+        .await
+        .unwrap());
+    assert!(!store
+        .insert_from_url(certificates[1].clone(), &second)
+        .await
+        .unwrap());
+    assert_eq!(
+        Some(&CertificateOrigin::Url(first)),
+        store.origin(fingerprint.as_slice())
+    );
 
-    let store = CertificateStore::from_iter(certificates.clone());
+    // latest origin recorded, priority kept
+    store.set_update_origin(true);
+    assert!(!store.insert(certificates[1].clone()));
+    assert!(!store
+        .insert_from_url(certificates[1].clone(), &second)
+        .await
+        .unwrap());
     assert_eq!(
-        vec![Arc::new(certificates[1].clone())],
-        store.issuers(&certificates[0])
+        Some(&CertificateOrigin::Url(second)),
+        store.origin(fingerprint.as_slice())
+    );
+    assert_eq!(
+        certificates,
+        store
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
     );
 
-    assert!(store.issuers(&certificates[1]).is_empty());
+    // configured certificate downloaded again stays pinned
+    let configured = crate::store::fingerprint(&certificates[0]).unwrap();
+    assert!(!store
+        .insert_from_url(certificates[0].clone(), &third)
+        .await
+        .unwrap());
+    assert_eq!(
+        Some(&CertificateOrigin::Url(third)),
+        store.origin(configured.as_slice())
+    );
+    assert_eq!(
+        vec![certificates[1].clone()],
+        store.set_eviction(EvictionConfiguration {
+            max_certificates: 1,
+            ..Default::default()
+        })
+    );
+    assert!(store.get(configured.as_slice()).is_some());
+}
+
+// store pinning ee, with intermediates downloaded from aia
+async fn aia_store(eviction: EvictionConfiguration) -> (MemoryStore, Vec<Arc<crate::Certificate>>) {
+    let certificates = CertificatePathGenerator::generate(5, "evict")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let url = Url::parse("test://aia").unwrap();
+
+    let mut store = MemoryStore::new();
+    store.set_eviction(eviction);
+    assert!(store.insert(certificates[0].clone()));
+    for certificate in certificates[1..3].iter() {
+        assert!(store
+            .insert_from_url(certificate.clone(), &url)
+            .await
+            .unwrap());
+    }
+    (store, certificates)
+}
+
+#[tokio::test]
+async fn test_eviction_lru() {
+    let (mut store, certificates) = aia_store(EvictionConfiguration {
+        max_certificates: 3,
+        ..Default::default()
+    })
+    .await;
+
+    // 1 recently used, 2 evicted
+    store.issuers(&certificates[0]).await.unwrap();
+    store
+        .insert_from_url(certificates[3].clone(), &Url::parse("test://aia").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            certificates[0].clone(),
+            certificates[1].clone(),
+            certificates[3].clone()
+        ],
+        store
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
+    );
+
+    // pinned never evicted
+    assert_eq!(
+        vec![certificates[1].clone(), certificates[3].clone()],
+        store.set_eviction(EvictionConfiguration {
+            max_certificates: 1,
+            ..Default::default()
+        })
+    );
+    assert!(store.insert(certificates[2].clone()));
+    assert_eq!(2, store.len());
+}
+
+#[tokio::test]
+async fn test_eviction_lfu() {
+    let (mut store, certificates) = aia_store(EvictionConfiguration {
+        max_certificates: 3,
+        policy: EvictionPolicy::Lfu,
+        ..Default::default()
+    })
+    .await;
+
+    // 1 used twice, 2 once. new download 3 kept, evicting least used 2
+    store.issuers(&certificates[0]).await.unwrap();
+    store.issuers(&certificates[0]).await.unwrap();
+    store.issuers(&certificates[1]).await.unwrap();
+    assert!(store
+        .insert_from_url(certificates[3].clone(), &Url::parse("test://aia").unwrap())
+        .await
+        .unwrap());
+    assert_eq!(
+        vec![
+            certificates[0].clone(),
+            certificates[1].clone(),
+            certificates[3].clone()
+        ],
+        store
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
+    );
+
+    // nothing else evictable, so new download not kept
+    let mut pinned = MemoryStore::from_iter(certificates[..3].to_vec());
+    pinned.set_eviction(EvictionConfiguration {
+        max_certificates: 3,
+        policy: EvictionPolicy::Lfu,
+        ..Default::default()
+    });
+    assert!(!pinned
+        .insert_from_url(certificates[3].clone(), &Url::parse("test://aia").unwrap())
+        .await
+        .unwrap());
+    assert_eq!(3, pinned.len());
+}
+
+#[tokio::test]
+async fn test_eviction_bytes() {
+    let (mut store, certificates) = aia_store(EvictionConfiguration::default()).await;
+    let size = certificates[..3]
+        .iter()
+        .map(|c| c.to_der().unwrap().len())
+        .sum::<usize>();
+    assert_eq!(size, store.size());
+
+    let evicted = store.set_eviction(EvictionConfiguration {
+        max_bytes: size - 1,
+        ..Default::default()
+    });
+    assert_eq!(vec![certificates[1].clone()], evicted);
+    assert_eq!(size - certificates[1].to_der().unwrap().len(), store.size());
+}
+
+#[tokio::test]
+async fn test_eviction_expired() {
+    let (mut store, certificates) = aia_store(EvictionConfiguration::default()).await;
+
+    // unsigned copy of issuer, expired
+    let mut expired = certificates[2].as_ref().clone();
+    expired.tbs_certificate.validity.not_after =
+        Time::try_from(std::time::UNIX_EPOCH + Duration::from_secs(1_000_000_000)).unwrap();
+    let expired = Arc::new(expired);
+    assert!(store.insert(expired.clone()));
+    assert_eq!(2, store.issuers(&certificates[1]).await.unwrap().len());
+
+    // hidden from issuers once configured, then evicted though pinned
+    store.set_eviction(EvictionConfiguration {
+        expired: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        vec![(
+            certificates[2].clone(),
+            CertificateOrigin::Url(Url::parse("test://aia").unwrap())
+        )],
+        store.issuers(&certificates[1]).await.unwrap()
+    );
+    assert!(store
+        .get(fingerprint(&expired).unwrap().as_slice())
+        .is_none());
+    assert!(store.evict().is_empty());
+}
+
+#[tokio::test]
+async fn test_self_signed() {
+    let certificates = CertificatePathGenerator::generate(3, "issuers")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    let root = certificates[2].clone();
+
+    let mut store = MemoryStore::new();
+    assert!(!store.insert(root.clone()));
+
+    store.set_self_signed(SelfSignedPolicy::Include);
+    assert!(store.insert(root.clone()));
+    assert!(!store.insert(root.clone()));
+    assert_eq!(
+        vec![(root.clone(), CertificateOrigin::Store)],
+        store.issuers(&certificates[1]).await.unwrap()
+    );
+    assert_eq!(
+        vec![(root.clone(), CertificateOrigin::Store)],
+        store.issuers(&root).await.unwrap()
+    );
+
+    // kept when excluding again
+    store.set_self_signed(SelfSignedPolicy::Exclude);
+    assert_eq!(1, store.len());
+}
+
+#[cfg(feature = "persist")]
+#[tokio::test]
+async fn test_snapshot() {
+    let mut certificates = CertificatePathGenerator::generate(5, "issuers")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    certificates.pop().unwrap();
+    let url = Url::parse("test://issuers").unwrap();
+
+    let mut store = MemoryStore::from_iter(certificates[..2].to_vec());
+    for certificate in certificates[2..].iter() {
+        assert!(store
+            .insert_from_url(certificate.clone(), &url)
+            .await
+            .unwrap());
+    }
+    let snapshot = store.snapshot().unwrap();
+
+    // priority and origins restored
+    let mut restored = MemoryStore::new();
+    assert_eq!(4, restored.restore(snapshot.as_slice()).unwrap());
+    assert_eq!(
+        store.iter().collect::<Vec<&Arc<crate::Certificate>>>(),
+        restored.iter().collect::<Vec<&Arc<crate::Certificate>>>()
+    );
+    for certificate in certificates.iter() {
+        let fingerprint = fingerprint(certificate).unwrap();
+        assert_eq!(
+            store.origin(fingerprint.as_slice()),
+            restored.origin(fingerprint.as_slice())
+        );
+    }
+
+    // stored certificates skipped
+    assert_eq!(0, restored.restore(snapshot.as_slice()).unwrap());
+    assert_eq!(4, restored.len());
+
+    // downloaded certificates remain evictable
+    restored.set_eviction(EvictionConfiguration {
+        max_certificates: 2,
+        ..Default::default()
+    });
+    assert_eq!(
+        certificates[..2].to_vec(),
+        restored
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
+    );
+
+    // malformed snapshot inserts nothing
+    let mut empty = MemoryStore::new();
+    assert!(empty.restore(&snapshot[..snapshot.len() - 1]).is_err());
+    assert!(empty
+        .restore(
+            br#"{"version":2,"certificates":[{"certificate":"AAAA","url":null,"pinned":true}]}"#
+        )
+        .is_err());
+    assert!(empty
+        .restore(br#"{"version":1,"certificates":[]}"#)
+        .is_err());
+    assert!(empty.is_empty());
+}
+
+#[cfg(feature = "persist")]
+#[tokio::test]
+async fn test_snapshot_pinned() {
+    let mut certificates = CertificatePathGenerator::generate(4, "pinned")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<crate::Certificate>>>();
+    certificates.pop().unwrap();
+    let url = Url::parse("test://pinned").unwrap();
+
+    // configured certificate downloaded again, origin updated to url
+    let mut store = MemoryStore::from_iter(certificates[..1].to_vec());
+    store.set_update_origin(true);
+    assert!(!store
+        .insert_from_url(certificates[0].clone(), &url)
+        .await
+        .unwrap());
+    for certificate in certificates[1..].iter() {
+        assert!(store
+            .insert_from_url(certificate.clone(), &url)
+            .await
+            .unwrap());
+    }
+    let fingerprint = fingerprint(&certificates[0]).unwrap();
+    assert_eq!(
+        Some(&CertificateOrigin::Url(url.clone())),
+        store.origin(fingerprint.as_slice())
+    );
+
+    // still pinned after restore, downloaded certificates evicted
+    let mut restored = MemoryStore::new();
+    assert_eq!(
+        3,
+        restored
+            .restore(store.snapshot().unwrap().as_slice())
+            .unwrap()
+    );
+    assert_eq!(
+        Some(&CertificateOrigin::Url(url)),
+        restored.origin(fingerprint.as_slice())
+    );
+    restored.set_eviction(EvictionConfiguration {
+        max_certificates: 1,
+        ..Default::default()
+    });
+    assert_eq!(
+        certificates[..1].to_vec(),
+        restored
+            .iter()
+            .cloned()
+            .collect::<Vec<Arc<crate::Certificate>>>()
+    );
 }
@@ -5,10 +5,18 @@ use x509_cert::Certificate;
 #[derive(Clone)]
 pub struct TestPathValidator {
     store: Vec<Certificate>,
+    vetoes: Vec<Certificate>,
 }
 impl TestPathValidator {
     pub fn new(store: Vec<Certificate>) -> Self {
-        Self { store }
+        Self {
+            store,
+            vetoes: vec![],
+        }
+    }
+
+    pub fn new_with_vetoes(store: Vec<Certificate>, vetoes: Vec<Certificate>) -> Self {
+        Self { store, vetoes }
     }
 }
 
@@ -37,4 +45,17 @@ impl PathValidator for TestPathValidator {
             "path not fond".to_string(),
         ))
     }
+
+    fn check_link(
+        &self,
+        _: &Certificate,
+        issuer: &Certificate,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        if self.vetoes.contains(issuer) {
+            return Ok(CertificatePathValidation::NotFound(
+                "issuer vetoed".to_string(),
+            ));
+        }
+        Ok(CertificatePathValidation::Found)
+    }
 }
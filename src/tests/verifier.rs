@@ -0,0 +1,50 @@
+use crate::provided::verifier::rustls::RustlsVerifier;
+use crate::report::CertificateOrigin;
+use crate::store::SelfSignedPolicy;
+use crate::tests::test_validator::TestPathValidator;
+use crate::{TestAIA, X509PathFinderConfiguration};
+use der::Encode;
+use rustls::server::ClientCertVerifier;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use url::Url;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+#[test]
+fn test_shared_store() {
+    let mut certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let root = certificates.pop().unwrap();
+    let ee = rustls::Certificate(certificates[0].to_der().unwrap());
+    let url = Url::parse("test://1.authority").unwrap();
+
+    let mut aia = HashMap::new();
+    aia.insert(url.clone(), Arc::new(certificates[1].clone()));
+
+    let verifier = RustlsVerifier::new(X509PathFinderConfiguration {
+        limit: Duration::default(),
+        aia: Some(TestAIA {
+            certificates: aia,
+            sleep: None,
+        }),
+        validator: TestPathValidator::new(vec![root]),
+        certificates: vec![],
+        anchors: vec![],
+        horizon: Duration::default(),
+        self_signed: SelfSignedPolicy::Exclude,
+    });
+    assert!(verifier.store().is_empty());
+
+    // downloaded intermediate cached for later verifications, presented end-entity isn't
+    for _ in 0..2 {
+        assert!(verifier
+            .verify_client_cert(&ee, std::slice::from_ref(&ee), SystemTime::now())
+            .is_ok());
+        let fingerprint = crate::store::fingerprint(&certificates[1]).unwrap();
+        assert_eq!(1, verifier.store().len());
+        assert_eq!(
+            Some(&CertificateOrigin::Url(url.clone())),
+            verifier.store().origin(fingerprint.as_slice())
+        );
+    }
+}
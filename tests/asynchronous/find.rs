@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Duration;
+use x509_path_finder::api::{
+    AsyncPathValidator, CertificatePathValidation, PathValidator, RevocationEvidence,
+};
+use x509_path_finder::provided::validator::cache::AsyncCachePathValidator;
+use x509_path_finder::provided::validator::composite::AsyncAllPathValidator;
+use x509_path_finder::provided::validator::default::result::DefaultPathValidatorError;
+use x509_path_finder::provided::validator::dynamic::DynAsyncPathValidator;
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+struct TestAsyncPathValidator {
+    root: x509_path_finder::Certificate,
+}
+
+#[async_trait]
+impl AsyncPathValidator for TestAsyncPathValidator {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    async fn validate(
+        &self,
+        path: Vec<&x509_path_finder::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        // stand-in for network i/o
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        match path.last() {
+            Some(ic) if ic.tbs_certificate.issuer == self.root.tbs_certificate.subject => {
+                Ok(CertificatePathValidation::Found)
+            }
+            _ => Ok(CertificatePathValidation::NotFound(
+                "path not found".to_string(),
+            )),
+        }
+    }
+}
+
+// not Sync
+struct TestCellPathValidator {
+    calls: Cell<usize>,
+}
+
+impl PathValidator for TestCellPathValidator {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        _: Vec<&x509_path_finder::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.calls.set(self.calls.get() + 1);
+        Ok(CertificatePathValidation::Found)
+    }
+}
+
+fn configuration<V: AsyncPathValidator>(
+    validator: V,
+    certificates: Vec<Arc<x509_path_finder::Certificate>>,
+) -> X509PathFinderConfiguration<V> {
+    X509PathFinderConfiguration {
+        certificates,
+        ..X509PathFinderConfiguration::new(validator)
+    }
+}
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DynAsyncPathValidator>();
+    assert_send_sync::<AsyncAllPathValidator>();
+}
+
+#[tokio::test]
+async fn test_find() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    let root = certificates.pop().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates,
+        ..X509PathFinderConfiguration::new(TestAsyncPathValidator {
+            root: root.as_ref().clone(),
+        })
+    });
+
+    let report = search.find(ee).await.unwrap();
+    let found = report.found.unwrap();
+
+    assert_eq!(0, report.failures.len());
+    assert_eq!(expected, found.path);
+}
+
+#[tokio::test]
+async fn test_find_not_sync() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    certificates.pop().unwrap();
+    let ee = certificates.remove(0);
+
+    let mut search = X509PathFinder::new(configuration(
+        TestCellPathValidator {
+            calls: Cell::new(0),
+        },
+        certificates,
+    ));
+
+    let report = search.find(ee).await.unwrap();
+    assert!(report.found.is_some());
+}
+
+#[tokio::test]
+async fn test_composite() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    let root = certificates.pop().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    let mut validator = AsyncAllPathValidator::new();
+    validator.push(TestAsyncPathValidator {
+        root: root.as_ref().clone(),
+    });
+    validator.push(DynAsyncPathValidator::new(TestAsyncPathValidator {
+        root: root.as_ref().clone(),
+    }));
+
+    let mut search = X509PathFinder::new(configuration(validator, certificates));
+
+    let report = search.find(ee).await.unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+}
+
+#[tokio::test]
+async fn test_cache() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    let root = certificates.pop().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    let validator = AsyncCachePathValidator::new(
+        TestAsyncPathValidator {
+            root: root.as_ref().clone(),
+        },
+        100,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+
+    let mut search = X509PathFinder::new(configuration(validator, certificates));
+
+    let report = search.find(ee.clone()).await.unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+    assert_eq!(0, report.cache.hits);
+
+    // path already validated
+    let report = search.find(ee.clone()).await.unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+    assert_eq!(0, report.cache.misses);
+
+    // path validated again with other supplied evidence
+    let report = search
+        .find_with_evidence(ee, vec![RevocationEvidence::Crl(vec![1])])
+        .await
+        .unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+    assert_eq!(1, report.cache.misses);
+}
@@ -0,0 +1,230 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use x509_path_finder::api::{
+    CertificatePathValidation, Evidence, PathValidator, RevocationEvidence,
+};
+use x509_path_finder::provided::validator::cache::CachePathValidator;
+use x509_path_finder::provided::validator::default::result::DefaultPathValidatorError;
+use x509_path_finder::report::CacheCounters;
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+#[derive(Default)]
+struct TestPathValidator {
+    calls: AtomicUsize,
+}
+
+impl PathValidator for TestPathValidator {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        path: Vec<&x509_path_finder::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match path.last() {
+            Some(ic) if ic.tbs_certificate.issuer.to_string() == "CN=0" => {
+                Ok(CertificatePathValidation::Found)
+            }
+            _ => Ok(CertificatePathValidation::NotFound(
+                "path not found".to_string(),
+            )),
+        }
+    }
+
+    // reports all supplied revocation evidence as used
+    fn validate_with_evidence(
+        &self,
+        path: Vec<&x509_path_finder::Certificate>,
+        evidence: &Evidence,
+    ) -> Result<(CertificatePathValidation, Vec<RevocationEvidence>), Self::PathValidatorError>
+    {
+        Ok((self.validate(path)?, evidence.revocation.clone()))
+    }
+}
+
+fn calls(validator: &CachePathValidator<TestPathValidator>) -> usize {
+    validator.validator().calls.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_cache() {
+    let certificates = CertificatePathGenerator::generate(4, "0").unwrap();
+
+    let validator = CachePathValidator::new(
+        TestPathValidator::default(),
+        10,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+
+    for _ in 0..3 {
+        assert_eq!(
+            CertificatePathValidation::Found,
+            validator
+                .validate(certificates[..3].iter().collect())
+                .unwrap()
+        );
+    }
+    assert_eq!(
+        CertificatePathValidation::NotFound("path not found".to_string()),
+        validator
+            .validate(certificates[..2].iter().collect())
+            .unwrap()
+    );
+
+    assert_eq!(2, validator.len());
+    assert_eq!(2, calls(&validator));
+    assert_eq!(
+        CacheCounters { hits: 2, misses: 2 },
+        validator.cache_counters()
+    );
+
+    validator.clear();
+    assert!(validator.is_empty());
+}
+
+#[test]
+fn test_evidence() {
+    let certificates = CertificatePathGenerator::generate(4, "0").unwrap();
+
+    let validator = CachePathValidator::new(
+        TestPathValidator::default(),
+        10,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+
+    let revocation = vec![RevocationEvidence::Ocsp(vec![1])];
+    let evidence = Evidence::from(revocation.clone());
+    for _ in 0..2 {
+        assert_eq!(
+            (CertificatePathValidation::Found, revocation.clone()),
+            validator
+                .validate_with_evidence(certificates[..3].iter().collect(), &evidence)
+                .unwrap()
+        );
+    }
+    assert_eq!(1, calls(&validator));
+
+    // keyed by supplied evidence
+    assert_eq!(
+        (CertificatePathValidation::Found, vec![]),
+        validator
+            .validate_with_evidence(certificates[..3].iter().collect(), &Evidence::default())
+            .unwrap()
+    );
+    assert_eq!(
+        (CertificatePathValidation::Found, revocation),
+        validator
+            .validate_with_evidence(
+                certificates[..3].iter().collect(),
+                &Evidence {
+                    scts: vec![vec![1]],
+                    ..evidence
+                }
+            )
+            .unwrap()
+    );
+    assert_eq!(3, calls(&validator));
+    assert_eq!(
+        CacheCounters { hits: 1, misses: 3 },
+        validator.cache_counters()
+    );
+}
+
+#[test]
+fn test_capacity() {
+    let certificates = CertificatePathGenerator::generate(4, "0").unwrap();
+
+    let validator = CachePathValidator::new(
+        TestPathValidator::default(),
+        2,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+
+    validator
+        .validate(certificates[..1].iter().collect())
+        .unwrap();
+    validator
+        .validate(certificates[..2].iter().collect())
+        .unwrap();
+    // first path is now most recently used
+    validator
+        .validate(certificates[..1].iter().collect())
+        .unwrap();
+    // evicts second path
+    validator
+        .validate(certificates[..3].iter().collect())
+        .unwrap();
+    assert_eq!(2, validator.len());
+
+    validator
+        .validate(certificates[..1].iter().collect())
+        .unwrap();
+    assert_eq!(3, calls(&validator));
+
+    validator
+        .validate(certificates[..2].iter().collect())
+        .unwrap();
+    assert_eq!(4, calls(&validator));
+}
+
+#[test]
+fn test_ttl() {
+    let certificates = CertificatePathGenerator::generate(4, "0").unwrap();
+
+    let validator = CachePathValidator::new(
+        TestPathValidator::default(),
+        10,
+        Duration::from_millis(50),
+        Duration::ZERO,
+    );
+
+    validator
+        .validate(certificates[..3].iter().collect())
+        .unwrap();
+    validator
+        .validate(certificates[..3].iter().collect())
+        .unwrap();
+    assert_eq!(1, calls(&validator));
+
+    std::thread::sleep(Duration::from_millis(100));
+    validator
+        .validate(certificates[..3].iter().collect())
+        .unwrap();
+    assert_eq!(2, calls(&validator));
+}
+
+#[tokio::test]
+async fn test_find() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    certificates.pop().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates,
+        ..X509PathFinderConfiguration::new(CachePathValidator::new(
+            TestPathValidator::default(),
+            100,
+            Duration::ZERO,
+            Duration::from_secs(3600),
+        ))
+    });
+
+    let report = search.find(ee.clone()).await.unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+    assert_eq!(CacheCounters { hits: 0, misses: 7 }, report.cache);
+
+    // links and path already validated
+    let report = search.find(ee).await.unwrap();
+    assert_eq!(expected, report.found.unwrap().path);
+    assert_eq!(CacheCounters { hits: 7, misses: 0 }, report.cache);
+}
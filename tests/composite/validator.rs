@@ -0,0 +1,141 @@
+use der::Encode;
+use std::fmt::{Display, Formatter};
+use webpki::{KeyUsage, TrustAnchor};
+use x509_path_finder::api::{CertificatePathValidation, PathValidator, PathValidatorError};
+use x509_path_finder::provided::validator::composite::{
+    AllPathValidator, AnyPathValidator, FallbackPathValidator,
+};
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+#[derive(Debug)]
+struct TestError;
+
+impl Display for TestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "test error")
+    }
+}
+
+impl PathValidatorError for TestError {}
+
+struct TestPathValidator(Option<CertificatePathValidation>);
+
+impl PathValidator for TestPathValidator {
+    type PathValidatorError = TestError;
+
+    fn validate(
+        &self,
+        _: Vec<&x509_path_finder::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        self.0.clone().ok_or(TestError)
+    }
+}
+
+fn found() -> TestPathValidator {
+    TestPathValidator(Some(CertificatePathValidation::Found))
+}
+
+fn not_found(reason: &str) -> TestPathValidator {
+    TestPathValidator(Some(CertificatePathValidation::NotFound(
+        reason.to_string(),
+    )))
+}
+
+fn error() -> TestPathValidator {
+    TestPathValidator(None)
+}
+
+#[test]
+fn test_all() {
+    let certificates = CertificatePathGenerator::generate(3, "0").unwrap();
+
+    let mut validator = AllPathValidator::new();
+    assert_eq!(
+        CertificatePathValidation::NotFound("no validators".to_string()),
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+
+    validator.push(found());
+    validator.push(found());
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+
+    validator.push(not_found("a"));
+    validator.push(found());
+    validator.push(not_found("b"));
+    assert_eq!(
+        CertificatePathValidation::NotFound("a; b".to_string()),
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+
+    validator.push(error());
+    assert!(validator.validate(certificates.iter().collect()).is_err());
+}
+
+#[test]
+fn test_any() {
+    let certificates = CertificatePathGenerator::generate(3, "0").unwrap();
+
+    let mut validator = AnyPathValidator::new();
+    assert_eq!(
+        CertificatePathValidation::NotFound("no validators".to_string()),
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+
+    validator.push(not_found("a"));
+    validator.push(not_found("b"));
+    assert_eq!(
+        CertificatePathValidation::NotFound("a; b".to_string()),
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+
+    validator.push(found());
+    validator.push(error());
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+}
+
+#[test]
+fn test_fallback() {
+    let certificates = CertificatePathGenerator::generate(3, "0").unwrap();
+
+    let mut validator = FallbackPathValidator::new();
+    validator.push(error());
+    validator.push(error());
+    assert!(validator.validate(certificates.iter().collect()).is_err());
+
+    validator.push(not_found("a"));
+    validator.push(found());
+    assert_eq!(
+        CertificatePathValidation::NotFound("a".to_string()),
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+}
+
+#[test]
+fn test_mixed_error_types() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0").unwrap();
+    let root = certificates.pop().unwrap().to_der().unwrap();
+    let root = TrustAnchor::try_from_cert_der(root.as_slice()).unwrap();
+
+    let algorithms = &[&webpki::ECDSA_P256_SHA256];
+
+    let mut validator = AllPathValidator::new();
+    validator.push(DefaultPathValidator::new_sync(
+        algorithms,
+        vec![root],
+        KeyUsage::client_auth(),
+        &[],
+    ));
+    validator.push(found());
+
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator.validate(certificates.iter().collect()).unwrap()
+    );
+}
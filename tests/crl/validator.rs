@@ -0,0 +1,332 @@
+use async_trait::async_trait;
+use der::asn1::OctetString;
+use der::oid::AssociatedOid;
+use der::Encode;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use webpki::KeyUsage;
+use x509_cert::ext::pkix::KeyUsages;
+use x509_cert::ext::Extension;
+use x509_path_finder::anchor::TrustAnchor;
+use x509_path_finder::api::{
+    AsyncPathValidator, CertificatePathValidation, Fetcher, FetcherError, RevocationEvidence,
+};
+use x509_path_finder::provided::validator::crl::CrlPathValidator;
+use x509_path_finder::provided::validator::default::{
+    DefaultPathValidator, SyncDefaultPathValidator,
+};
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+#[derive(Debug)]
+struct TestFetcherError(Url);
+
+impl Display for TestFetcherError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not found: {}", self.0)
+    }
+}
+
+impl FetcherError for TestFetcherError {
+    fn unsupported(_operation: &str, url: &Url) -> Self {
+        Self(url.clone())
+    }
+}
+
+// in-process stand-in for CRL distribution points
+#[derive(Default)]
+struct TestFetcher {
+    crls: HashMap<Url, Vec<u8>>,
+    fetches: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Fetcher for TestFetcher {
+    type FetcherError = TestFetcherError;
+
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, Self::FetcherError> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        self.crls
+            .get(url)
+            .cloned()
+            .ok_or_else(|| TestFetcherError(url.clone()))
+    }
+}
+
+struct Material {
+    certificates: Vec<x509_cert::Certificate>,
+    keys: Vec<Vec<u8>>,
+    root: Vec<u8>,
+}
+
+impl Material {
+    fn generate() -> Self {
+        let (certificates, keys) = CertificatePathGenerator::generate_with_keys(4, "0").unwrap();
+        let root = certificates.last().unwrap().to_der().unwrap();
+        Self {
+            certificates,
+            keys,
+            root,
+        }
+    }
+
+    fn root(&self) -> &x509_cert::Certificate {
+        self.certificates.last().unwrap()
+    }
+
+    fn path(&self) -> Vec<&x509_cert::Certificate> {
+        self.certificates[..self.certificates.len() - 1]
+            .iter()
+            .collect()
+    }
+
+    fn url(&self, issuer: usize) -> Url {
+        let name = self.certificates[issuer]
+            .tbs_certificate
+            .subject
+            .to_string();
+        Url::parse(format!("test://{}/crl", name.trim_start_matches("CN=")).as_str()).unwrap()
+    }
+
+    // crl for every issuer in the path, revoking `revoked`
+    fn fetcher(&self, revoked: &[usize]) -> TestFetcher {
+        let mut crls = HashMap::new();
+        for issuer in 1..self.certificates.len() {
+            let revoked = revoked
+                .iter()
+                .filter(|r| **r + 1 == issuer)
+                .map(|r| &self.certificates[*r])
+                .collect::<Vec<&x509_cert::Certificate>>();
+            crls.insert(
+                self.url(issuer),
+                CertificatePathGenerator::crl(
+                    &self.certificates[issuer],
+                    &self.keys[issuer],
+                    revoked.as_slice(),
+                    Duration::from_secs(3600),
+                )
+                .unwrap(),
+            );
+        }
+        TestFetcher {
+            crls,
+            fetches: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn validator(
+        &self,
+        fetcher: TestFetcher,
+    ) -> CrlPathValidator<'_, SyncDefaultPathValidator<'_>, TestFetcher> {
+        let root = webpki::TrustAnchor::try_from_cert_der(self.root.as_slice()).unwrap();
+        CrlPathValidator::new(
+            DefaultPathValidator::new_sync(ALGORITHMS, vec![root], KeyUsage::client_auth(), &[]),
+            fetcher,
+            ALGORITHMS,
+            vec![TrustAnchor::try_from(self.root()).unwrap()],
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_not_revoked() {
+    let material = Material::generate();
+    let fetcher = material.fetcher(&[]);
+    let fetches = fetcher.fetches.clone();
+    let validator = material.validator(fetcher);
+
+    for _ in 0..2 {
+        assert_eq!(
+            CertificatePathValidation::Found,
+            validator.validate(material.path()).await.unwrap()
+        );
+    }
+
+    // crls cached until next update
+    assert_eq!(3, fetches.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_revoked() {
+    let material = Material::generate();
+
+    // end-entity, and top certificate verified by anchor
+    for revoked in [0, 2] {
+        let validator = material.validator(material.fetcher(&[revoked]));
+        assert_eq!(
+            CertificatePathValidation::NotFound(format!(
+                "certificate revoked: {}",
+                material.certificates[revoked].tbs_certificate.subject
+            )),
+            validator.validate(material.path()).await.unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_unknown() {
+    let material = Material::generate();
+
+    // crl missing
+    let mut fetcher = material.fetcher(&[]);
+    fetcher.crls.remove(&material.url(1));
+    let validator = material.validator(fetcher);
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.starts_with("revocation status unknown"))
+        }
+        CertificatePathValidation::Found => panic!("revocation status is unknown"),
+    }
+
+    // crl signed by wrong key
+    let mut fetcher = material.fetcher(&[]);
+    fetcher.crls.insert(
+        material.url(1),
+        CertificatePathGenerator::crl(
+            &material.certificates[1],
+            &material.keys[0],
+            &[],
+            Duration::from_secs(3600),
+        )
+        .unwrap(),
+    );
+    let validator = material.validator(fetcher);
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.ends_with("crl signature not verified"))
+        }
+        CertificatePathValidation::Found => panic!("crl signature is invalid"),
+    }
+}
+
+#[tokio::test]
+async fn test_not_crl_signer() {
+    let mut material = Material::generate();
+
+    // issuer's key usage permits signing certificates only
+    let issuer = CertificatePathGenerator::without_extension(
+        &material.certificates[1],
+        &material.keys[2],
+        x509_cert::ext::pkix::KeyUsage::OID,
+    )
+    .unwrap();
+    material.certificates[1] = CertificatePathGenerator::with_extension(
+        &issuer,
+        &material.keys[2],
+        Extension {
+            extn_id: x509_cert::ext::pkix::KeyUsage::OID,
+            critical: true,
+            extn_value: OctetString::new(
+                x509_cert::ext::pkix::KeyUsage(KeyUsages::KeyCertSign.into())
+                    .to_der()
+                    .unwrap(),
+            )
+            .unwrap(),
+        },
+    )
+    .unwrap();
+
+    let validator = material.validator(material.fetcher(&[]));
+    assert_eq!(
+        CertificatePathValidation::NotFound(format!(
+            "crl issuer not permitted to sign crls: {}",
+            material.certificates[1].tbs_certificate.subject
+        )),
+        validator.validate(material.path()).await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_find() {
+    let material = Material::generate();
+    let certificates = material
+        .path()
+        .into_iter()
+        .map(|c| Arc::new(c.clone()))
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: certificates[1..].to_vec(),
+        ..X509PathFinderConfiguration::new(material.validator(material.fetcher(&[1])))
+    });
+
+    let report = search.find(certificates[0].clone()).await.unwrap();
+    assert!(report.found.is_none());
+    assert_eq!(
+        format!(
+            "certificate revoked: {}",
+            material.certificates[1].tbs_certificate.subject
+        ),
+        report.failures[0].reason
+    );
+}
+
+#[tokio::test]
+async fn test_evidence() {
+    let material = Material::generate();
+    let certificates = material
+        .path()
+        .into_iter()
+        .map(|c| Arc::new(c.clone()))
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+
+    // every crl supplied, none fetched
+    let supplied = material.fetcher(&[]);
+    let evidence = (1..material.certificates.len())
+        .map(|i| RevocationEvidence::Crl(supplied.crls[&material.url(i)].clone()))
+        .collect::<Vec<RevocationEvidence>>();
+    let fetcher = TestFetcher::default();
+    let fetches = fetcher.fetches.clone();
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: certificates[1..].to_vec(),
+        ..X509PathFinderConfiguration::new(material.validator(fetcher))
+    });
+
+    let report = search
+        .find_with_evidence(certificates[0].clone(), evidence.clone())
+        .await
+        .unwrap();
+    assert_eq!(evidence, report.found.unwrap().revocation);
+    assert_eq!(0, fetches.load(Ordering::SeqCst));
+
+    // supplied crl revoking end-entity is used before fetched crl
+    let revoked = material.fetcher(&[0]);
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: certificates[1..].to_vec(),
+        ..X509PathFinderConfiguration::new(material.validator(material.fetcher(&[])))
+    });
+
+    let report = search
+        .find_with_evidence(
+            certificates[0].clone(),
+            vec![RevocationEvidence::Crl(
+                revoked.crls[&material.url(1)].clone(),
+            )],
+        )
+        .await
+        .unwrap();
+    assert!(report.found.is_none());
+    assert_eq!(
+        format!(
+            "certificate revoked: {}",
+            material.certificates[0].tbs_certificate.subject
+        ),
+        report.failures[0].reason
+    );
+}
+
+#[tokio::test]
+async fn test_post_unsupported() {
+    let url = Url::parse("http://localhost/ocsp").unwrap();
+    let result = TestFetcher::default()
+        .post(&url, "application/ocsp-request", vec![])
+        .await;
+    assert_eq!(url, result.unwrap_err().0);
+}
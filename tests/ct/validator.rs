@@ -0,0 +1,265 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use der::Encode;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use webpki::KeyUsage;
+use x509_path_finder::anchor::TrustAnchor;
+use x509_path_finder::api::{CertificatePathValidation, Evidence, PathValidator};
+use x509_path_finder::provided::validator::ct::{CtLogList, CtPathValidator};
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+struct Material {
+    certificates: Vec<x509_cert::Certificate>,
+    keys: Vec<Vec<u8>>,
+    root: Vec<u8>,
+    // private and public keys of trusted logs
+    logs: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Material {
+    fn generate() -> Self {
+        let (certificates, keys) = CertificatePathGenerator::generate_with_keys(3, "0").unwrap();
+        let root = certificates.last().unwrap().to_der().unwrap();
+        Self {
+            certificates,
+            keys,
+            root,
+            logs: (0..2)
+                .map(|_| CertificatePathGenerator::ct_log_key().unwrap())
+                .collect(),
+        }
+    }
+
+    fn log_list(&self) -> String {
+        let logs = self
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(i, (_, key))| {
+                format!(
+                    r#"{{"description": "log {}", "key": "{}"}}"#,
+                    i,
+                    STANDARD.encode(key)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            r#"{{"version": "1.0", "operators": [{{"name": "operator", "email": [], "logs": [{}]}}]}}"#,
+            logs
+        )
+    }
+
+    // end-entity with scts embedded, from logs at `logs`
+    fn embedded(&self, logs: &[usize]) -> x509_cert::Certificate {
+        let scts = logs
+            .iter()
+            .map(|l| {
+                CertificatePathGenerator::precert_sct(
+                    &self.certificates[0],
+                    &self.certificates[1],
+                    &self.logs[*l].0,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<Vec<u8>>>();
+        CertificatePathGenerator::embed_scts(&self.certificates[0], &self.keys[1], &scts).unwrap()
+    }
+
+    fn validator(&self, minimum: usize) -> CtPathValidator<DefaultPathValidator<'_>> {
+        self.validator_with(
+            CtLogList::from_json(self.log_list().as_str()).unwrap(),
+            minimum,
+        )
+    }
+
+    fn validator_with(
+        &self,
+        logs: CtLogList,
+        minimum: usize,
+    ) -> CtPathValidator<DefaultPathValidator<'_>> {
+        let root = webpki::TrustAnchor::try_from_cert_der(self.root.as_slice()).unwrap();
+        CtPathValidator::new(
+            DefaultPathValidator::new(ALGORITHMS, vec![root], KeyUsage::client_auth(), &[]),
+            logs,
+            minimum,
+            vec![TrustAnchor::try_from(&self.certificates[2]).unwrap()],
+        )
+    }
+}
+
+#[test]
+fn test_log_list() {
+    let material = Material::generate();
+    let path = std::env::temp_dir().join(format!("ct-log-list-{}.json", std::process::id()));
+    std::fs::write(&path, material.log_list()).unwrap();
+
+    let logs = CtLogList::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(2, logs.logs().len());
+    assert_eq!("log 1", logs.logs()[1].description());
+    assert_eq!("operator", logs.logs()[1].operator());
+}
+
+#[test]
+fn test_log_state() {
+    let material = Material::generate();
+    let (pending, _) = CertificatePathGenerator::ct_log_key().unwrap();
+    let json = format!(
+        r#"{{"operators": [{{"name": "operator", "logs": [
+            {{"description": "log 0", "key": "{}", "state": {{"retired": {{"timestamp": "2020-01-01T00:00:00Z"}}}}}},
+            {{"description": "log 1", "key": "{}", "state": {{"usable": {{"timestamp": "2020-01-01T00:00:00Z"}}}},
+                "temporal_interval": {{"start_inclusive": "2000-01-01T00:00:00Z", "end_exclusive": "2001-01-01T00:00:00.5Z"}}}},
+            {{"description": "log 2", "key": "{}", "state": {{"rejected": {{"timestamp": "2020-01-01T00:00:00Z"}}}}}},
+            {{"description": "log 3", "key": "{}", "state": {{"pending": {{"timestamp": "2020-01-01T00:00:00Z"}}}}}}
+        ]}}]}}"#,
+        STANDARD.encode(&material.logs[0].1),
+        STANDARD.encode(&material.logs[1].1),
+        STANDARD.encode(&material.logs[0].1),
+        STANDARD.encode(pending),
+    );
+
+    let logs = CtLogList::from_json(json.as_str()).unwrap();
+    assert_eq!(1, logs.logs().len());
+    assert_eq!("log 1", logs.logs()[0].description());
+    assert_eq!(
+        Some((
+            UNIX_EPOCH + Duration::from_secs(946684800),
+            UNIX_EPOCH + Duration::from_secs(978307200)
+        )),
+        logs.logs()[0].temporal_interval()
+    );
+
+    // retired log untrusted, and target expires after the interval of the sharded log
+    let ee = material.embedded(&[0, 1]);
+    match material
+        .validator_with(logs, 1)
+        .validate(vec![&ee, &material.certificates[1]])
+        .unwrap()
+    {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.contains("unknown log"));
+            assert!(reason.ends_with("log 1: certificate expiry outside log temporal interval"));
+        }
+        CertificatePathValidation::Found => panic!("no trusted log accepts the target"),
+    }
+}
+
+#[test]
+fn test_embedded() {
+    let material = Material::generate();
+    let ee = material.embedded(&[0, 1]);
+
+    assert_eq!(
+        CertificatePathValidation::Found,
+        material
+            .validator(2)
+            .validate(vec![&ee, &material.certificates[1]])
+            .unwrap()
+    );
+
+    let ee = material.embedded(&[0]);
+    assert_eq!(
+        CertificatePathValidation::NotFound(
+            "ct policy not met: scts verified from 1 of 2 required logs".to_string()
+        ),
+        material
+            .validator(2)
+            .validate(vec![&ee, &material.certificates[1]])
+            .unwrap()
+    );
+
+    // no scts
+    assert_eq!(
+        CertificatePathValidation::NotFound(
+            "ct policy not met: scts verified from 0 of 1 required logs".to_string()
+        ),
+        material
+            .validator(1)
+            .validate(vec![&material.certificates[0], &material.certificates[1]])
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_unverified() {
+    let material = Material::generate();
+
+    // sct from untrusted log
+    let (untrusted, _) = CertificatePathGenerator::ct_log_key().unwrap();
+    let sct = CertificatePathGenerator::precert_sct(
+        &material.certificates[0],
+        &material.certificates[1],
+        &untrusted,
+    )
+    .unwrap();
+    let ee =
+        CertificatePathGenerator::embed_scts(&material.certificates[0], &material.keys[1], &[sct])
+            .unwrap();
+    match material
+        .validator(1)
+        .validate(vec![&ee, &material.certificates[1]])
+        .unwrap()
+    {
+        CertificatePathValidation::NotFound(reason) => assert!(reason.contains("unknown log")),
+        CertificatePathValidation::Found => panic!("sct log is untrusted"),
+    }
+
+    // sct over precertificate from another issuer
+    let sct = CertificatePathGenerator::precert_sct(
+        &material.certificates[0],
+        &material.certificates[2],
+        &material.logs[0].0,
+    )
+    .unwrap();
+    let ee =
+        CertificatePathGenerator::embed_scts(&material.certificates[0], &material.keys[1], &[sct])
+            .unwrap();
+    match material
+        .validator(1)
+        .validate(vec![&ee, &material.certificates[1]])
+        .unwrap()
+    {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.ends_with("log 0: sct signature not verified"))
+        }
+        CertificatePathValidation::Found => panic!("sct signature is invalid"),
+    }
+}
+
+#[tokio::test]
+async fn test_find() {
+    let material = Material::generate();
+    let ee = Arc::new(material.embedded(&[0]));
+    let stapled = CertificatePathGenerator::sct_list(&[CertificatePathGenerator::x509_sct(
+        ee.as_ref(),
+        &material.logs[1].0,
+    )
+    .unwrap()]);
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: vec![Arc::new(material.certificates[1].clone())],
+        ..X509PathFinderConfiguration::new(material.validator(2))
+    });
+
+    let report = search.find(ee.clone()).await.unwrap();
+    assert!(report.found.is_none());
+
+    // embedded and stapled scts from distinct logs
+    let report = search
+        .find_with_evidence(
+            ee,
+            Evidence {
+                scts: vec![stapled],
+                ..Evidence::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(report.found.is_some());
+}
@@ -1,5 +1,6 @@
 use der::Encode;
-use webpki::{KeyUsage, TrustAnchor};
+use std::time::Duration;
+use webpki::{BorrowedCertRevocationList, CertRevocationList, KeyUsage, TrustAnchor};
 use x509_path_finder::api::{CertificatePathValidation, PathValidator};
 use x509_path_finder::provided::validator::default::DefaultPathValidator;
 use x509_path_finder_material::generate::CertificatePathGenerator;
@@ -16,3 +17,41 @@ fn test_validator() {
     let validate = validator.validate(certificates.iter().collect()).unwrap();
     assert_eq!(CertificatePathValidation::Found, validate);
 }
+
+#[test]
+fn test_crls() {
+    let (mut certificates, keys) = CertificatePathGenerator::generate_with_keys(4, "0").unwrap();
+    let root = certificates.pop().unwrap().to_der().unwrap();
+    let crl = CertificatePathGenerator::crl(
+        &certificates[1],
+        &keys[1],
+        &[&certificates[0]],
+        Duration::from_secs(3600),
+    )
+    .unwrap();
+    let crl = BorrowedCertRevocationList::from_der(crl.as_slice()).unwrap();
+
+    let algorithms = &[&webpki::ECDSA_P256_SHA256];
+
+    // crls are checked
+    let crls: &[&dyn CertRevocationList] = &[&crl];
+    let anchor = TrustAnchor::try_from_cert_der(root.as_slice()).unwrap();
+    let validator =
+        DefaultPathValidator::new(algorithms, vec![anchor], KeyUsage::client_auth(), crls);
+    let validate = validator.validate(certificates.iter().collect()).unwrap();
+    assert_eq!(
+        CertificatePathValidation::NotFound("CertRevoked".to_string()),
+        validate
+    );
+
+    // sync validator checks the same crls
+    let crls: &[&(dyn CertRevocationList + Sync)] = &[&crl];
+    let anchor = TrustAnchor::try_from_cert_der(root.as_slice()).unwrap();
+    let validator =
+        DefaultPathValidator::new_sync(algorithms, vec![anchor], KeyUsage::client_auth(), crls);
+    let validate = validator.validate(certificates.iter().collect()).unwrap();
+    assert_eq!(
+        CertificatePathValidation::NotFound("CertRevoked".to_string()),
+        validate
+    );
+}
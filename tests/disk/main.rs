@@ -0,0 +1,3 @@
+#![cfg(feature = "persist")]
+
+mod store;
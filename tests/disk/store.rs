@@ -0,0 +1,191 @@
+use der::Encode;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use url::Url;
+use x509_path_finder::api::CertificateBackend;
+use x509_path_finder::store::conformance;
+use x509_path_finder::store::disk::DiskStore;
+use x509_path_finder::store::fingerprint;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+// empty scratch directory, unique per test
+fn scratch(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "x509-path-finder-disk-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_conformance() {
+    let dir = scratch("conformance");
+    let mut count = 0;
+    conformance::run(|| {
+        count += 1;
+        DiskStore::open(dir.join(count.to_string())).unwrap()
+    })
+    .await;
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload() {
+    let dir = scratch("reload");
+    let mut certificates = CertificatePathGenerator::generate(5, "authority")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_cert::Certificate>>>();
+    let root = certificates.pop().unwrap();
+    let url = Url::parse("http://example.com/2.authority.cer").unwrap();
+
+    let mut store = DiskStore::open(&dir).unwrap();
+    assert!(store.is_empty());
+    assert!(!store.insert(root).unwrap());
+    assert!(store.insert(certificates[3].clone()).unwrap());
+    assert!(store
+        .insert_from_url(certificates[1].clone(), &url)
+        .await
+        .unwrap());
+    assert!(store.insert(certificates[2].clone()).unwrap());
+    assert!(!store.insert(certificates[2].clone()).unwrap());
+    drop(store);
+
+    // same priority order and origin after reload
+    let mut store = DiskStore::open(&dir).unwrap();
+    assert!(store.skipped().is_empty());
+    assert_eq!(
+        vec![
+            certificates[3].clone(),
+            certificates[1].clone(),
+            certificates[2].clone()
+        ],
+        store
+            .iter()
+            .map(|e| e.certificate.clone())
+            .collect::<Vec<Arc<x509_cert::Certificate>>>()
+    );
+    let entry = store
+        .get(fingerprint(&certificates[1]).unwrap().as_slice())
+        .unwrap();
+    assert_eq!(Some(url), entry.url);
+    assert!(store.iter().next().unwrap().url.is_none());
+
+    // deduplicated across reloads, inserted with lowest priority
+    assert!(!store.insert(certificates[3].clone()).unwrap());
+    assert!(store.insert(certificates[0].clone()).unwrap());
+    assert_eq!(
+        &certificates[0],
+        &DiskStore::open(&dir)
+            .unwrap()
+            .iter()
+            .last()
+            .unwrap()
+            .certificate
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_skipped() {
+    let dir = scratch("skipped");
+    let certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+
+    let mut store = DiskStore::open(&dir).unwrap();
+    assert!(store.insert(certificates[0].clone()).unwrap());
+    assert!(store.insert(certificates[1].clone()).unwrap());
+
+    // metadata lost, and certificate under wrong name
+    let name = fingerprint(&certificates[0])
+        .unwrap()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    fs::remove_file(dir.join(format!("{}.json", name))).unwrap();
+    fs::write(dir.join("00.der"), certificates[1].to_der().unwrap()).unwrap();
+
+    let store = DiskStore::open(&dir).unwrap();
+    assert_eq!(1, store.len());
+    assert_eq!(2, store.skipped().len());
+    assert!(store
+        .skipped()
+        .iter()
+        .any(|s| s.reason.starts_with("missing metadata")));
+    assert!(store
+        .skipped()
+        .iter()
+        .any(|s| s.reason == "fingerprint mismatch"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_update_origin() {
+    let dir = scratch("origin");
+    let certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+    let fingerprint = fingerprint(&certificates[1]).unwrap();
+    let first = Url::parse("http://example.com/first.cer").unwrap();
+    let second = Url::parse("http://example.com/second.cer").unwrap();
+    let certificate = Arc::new(certificates[1].clone());
+
+    let mut store = DiskStore::open(&dir).unwrap();
+    assert!(store
+        .insert_from_url(certificate.clone(), &first)
+        .await
+        .unwrap());
+    assert!(!store
+        .insert_from_url(certificate.clone(), &second)
+        .await
+        .unwrap());
+    assert_eq!(
+        Some(&first),
+        store.get(fingerprint.as_slice()).unwrap().url.as_ref()
+    );
+
+    // latest origin persisted
+    store.set_update_origin(true);
+    assert!(store.insert(certificates[0].clone()).unwrap());
+    assert!(!store
+        .insert_from_url(certificate.clone(), &second)
+        .await
+        .unwrap());
+    let store = DiskStore::open(&dir).unwrap();
+    assert_eq!(
+        Some(&second),
+        store.get(fingerprint.as_slice()).unwrap().url.as_ref()
+    );
+    assert_eq!(certificate, store.iter().next().unwrap().certificate);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_temporary() {
+    let dir = scratch("temporary");
+    let certificates = CertificatePathGenerator::generate(3, "authority").unwrap();
+
+    let mut store = DiskStore::open(&dir).unwrap();
+    assert!(store.insert(certificates[0].clone()).unwrap());
+    let stray = dir.join(format!(
+        "{}.der.tmp",
+        hex(fingerprint(&certificates[1]).unwrap().as_slice())
+    ));
+    fs::write(&stray, certificates[1].to_der().unwrap()).unwrap();
+
+    // interrupted insert cleaned up, not reported as skipped
+    let store = DiskStore::open(&dir).unwrap();
+    assert!(!stray.exists());
+    assert!(store.skipped().is_empty());
+    assert_eq!(1, store.len());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
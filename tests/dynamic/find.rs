@@ -0,0 +1,53 @@
+use der::Encode;
+use std::sync::Arc;
+use webpki::{KeyUsage, TrustAnchor};
+use x509_path_finder::provided::validator::composite::AllPathValidator;
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder::provided::validator::dynamic::DynPathValidator;
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+fn select<'v>(name: &str, root: TrustAnchor<'v>) -> DynPathValidator<'v> {
+    let validator =
+        DefaultPathValidator::new_sync(ALGORITHMS, vec![root], KeyUsage::client_auth(), &[]);
+    match name {
+        "composite" => {
+            let mut composite = AllPathValidator::new();
+            composite.push(validator);
+            DynPathValidator::new(composite)
+        }
+        _ => DynPathValidator::new(validator),
+    }
+}
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DynPathValidator>();
+}
+
+#[tokio::test]
+async fn test_find() {
+    let mut certificates = CertificatePathGenerator::generate(8, "0")
+        .unwrap()
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    let root = certificates.pop().unwrap().to_der().unwrap();
+    let expected = certificates.clone();
+    let ee = certificates.remove(0);
+
+    for name in ["default", "composite"] {
+        let root = TrustAnchor::try_from_cert_der(root.as_slice()).unwrap();
+
+        let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+            certificates: certificates.clone(),
+            ..X509PathFinderConfiguration::new(select(name, root))
+        });
+
+        let found = search.find(ee.clone()).await.unwrap().found.unwrap();
+        assert_eq!(expected, found.path);
+    }
+}
@@ -0,0 +1,197 @@
+use der::asn1::{Ia5String, OctetString};
+use der::Encode;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use webpki::KeyUsage;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_path_finder::api::{CertificatePathValidation, PathValidator};
+use x509_path_finder::provided::validator::default::result::DefaultPathValidatorError;
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder::provided::validator::identity::{
+    verify_identity, IdentityMismatch, IdentityPathValidator, ServerIdentity,
+};
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+fn dns(name: &str) -> GeneralName {
+    GeneralName::DnsName(Ia5String::new(name).unwrap())
+}
+
+fn ip(ip: IpAddr) -> GeneralName {
+    let octets = match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    };
+    GeneralName::IpAddress(OctetString::new(octets).unwrap())
+}
+
+// path with end-entity presenting `names`
+fn generate(names: Vec<GeneralName>) -> Vec<x509_cert::Certificate> {
+    let (mut certificates, keys) = CertificatePathGenerator::generate_with_keys(3, "0").unwrap();
+    certificates[0] =
+        CertificatePathGenerator::with_subject_alt_names(&certificates[0], &keys[1], names)
+            .unwrap();
+    certificates
+}
+
+fn validator<'a>(
+    root: &'a [u8],
+    identity: &str,
+) -> IdentityPathValidator<DefaultPathValidator<'a>> {
+    IdentityPathValidator::new(
+        DefaultPathValidator::new(
+            ALGORITHMS,
+            vec![webpki::TrustAnchor::try_from_cert_der(root).unwrap()],
+            KeyUsage::client_auth(),
+            &[],
+        ),
+        ServerIdentity::parse(identity).unwrap(),
+    )
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        ServerIdentity::Dns("www.example.com".to_string()),
+        ServerIdentity::parse("WWW.Example.com.").unwrap()
+    );
+    assert_eq!(
+        ServerIdentity::Ip(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))),
+        ServerIdentity::parse("192.0.2.1").unwrap()
+    );
+    for invalid in ["", "*.example.com", "a..example.com", "-a.example.com"] {
+        assert!(ServerIdentity::parse(invalid).is_err());
+    }
+}
+
+#[test]
+fn test_verify() {
+    let certificates = generate(vec![
+        dns("www.example.com"),
+        dns("*.api.example.com"),
+        dns("*.com"),
+        dns("f*.example.org"),
+        ip("192.0.2.1".parse().unwrap()),
+        ip("2001:db8::1".parse().unwrap()),
+    ]);
+
+    for matched in [
+        "www.example.com",
+        "WWW.EXAMPLE.COM",
+        "v1.api.example.com",
+        "192.0.2.1",
+        "2001:db8::1",
+    ] {
+        assert!(
+            verify_identity(&certificates[0], &ServerIdentity::parse(matched).unwrap()).is_ok(),
+            "{} should match",
+            matched
+        );
+    }
+
+    // wildcard matches exactly one label, never a public suffix alone or a partial label
+    for mismatched in [
+        "example.com",
+        "api.example.com",
+        "a.v1.api.example.com",
+        "example.com.org",
+        "foo.example.org",
+        "192.0.2.2",
+    ] {
+        assert!(
+            verify_identity(
+                &certificates[0],
+                &ServerIdentity::parse(mismatched).unwrap()
+            )
+            .is_err(),
+            "{} should not match",
+            mismatched
+        );
+    }
+}
+
+#[test]
+fn test_mismatch() {
+    let certificates = generate(vec![
+        dns("www.example.com"),
+        ip("192.0.2.1".parse().unwrap()),
+    ]);
+    let root = certificates[2].to_der().unwrap();
+    let path = certificates[..2]
+        .iter()
+        .collect::<Vec<&x509_cert::Certificate>>();
+
+    let mismatch = IdentityMismatch {
+        expected: ServerIdentity::Dns("mail.example.com".to_string()),
+        subject: certificates[0].tbs_certificate.subject.to_string(),
+        presented: vec!["www.example.com".to_string(), "192.0.2.1".to_string()],
+    };
+    assert_eq!(
+        Err(mismatch.clone()),
+        verify_identity(&certificates[0], &mismatch.expected)
+    );
+    assert_eq!(
+        CertificatePathValidation::NotFound(mismatch.to_string()),
+        validator(root.as_slice(), "mail.example.com")
+            .validate(path.clone())
+            .unwrap()
+    );
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator(root.as_slice(), "www.example.com")
+            .validate(path)
+            .unwrap()
+    );
+}
+
+// finds every path valid
+struct TestPathValidator;
+
+impl PathValidator for TestPathValidator {
+    type PathValidatorError = DefaultPathValidatorError;
+
+    fn validate(
+        &self,
+        _path: Vec<&x509_cert::Certificate>,
+    ) -> Result<CertificatePathValidation, Self::PathValidatorError> {
+        Ok(CertificatePathValidation::Found)
+    }
+}
+
+#[test]
+fn test_empty() {
+    let validator = IdentityPathValidator::new(
+        TestPathValidator,
+        ServerIdentity::parse("www.example.com").unwrap(),
+    );
+    assert_eq!(
+        CertificatePathValidation::NotFound("path is empty".to_string()),
+        validator.validate(vec![]).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_find() {
+    let certificates = generate(vec![dns("*.example.com")]);
+    let root = certificates[2].to_der().unwrap();
+
+    for (identity, found) in [("www.example.com", true), ("www.example.org", false)] {
+        let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+            certificates: vec![Arc::new(certificates[1].clone())],
+            ..X509PathFinderConfiguration::new(validator(root.as_slice(), identity))
+        });
+
+        let report = search
+            .find(Arc::new(certificates[0].clone()))
+            .await
+            .unwrap();
+        assert_eq!(found, report.found.is_some());
+        if !found {
+            assert!(report.failures[0]
+                .reason
+                .starts_with("identity mismatch: expected www.example.org"));
+        }
+    }
+}
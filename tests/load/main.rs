@@ -0,0 +1,3 @@
+#![cfg(feature = "pkcs7")]
+
+mod loader;
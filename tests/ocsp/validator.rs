@@ -0,0 +1,383 @@
+use async_trait::async_trait;
+use der::Encode;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use url::Url;
+use webpki::KeyUsage;
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_path_finder::anchor::TrustAnchor;
+use x509_path_finder::api::{
+    AsyncPathValidator, CertificatePathValidation, Fetcher, FetcherError, RevocationEvidence,
+};
+use x509_path_finder::provided::validator::default::{
+    DefaultPathValidator, SyncDefaultPathValidator,
+};
+use x509_path_finder::provided::validator::ocsp::{OcspFailure, OcspPathValidator};
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+#[derive(Debug)]
+struct TestResponderError(Url);
+
+impl Display for TestResponderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no responder: {}", self.0)
+    }
+}
+
+impl FetcherError for TestResponderError {
+    fn unsupported(_operation: &str, url: &Url) -> Self {
+        Self(url.clone())
+    }
+}
+
+// signing certificate and key of each responder
+struct Responder {
+    issuer: x509_cert::Certificate,
+    key: Vec<u8>,
+}
+
+// in-process stand-in for OCSP responders
+struct TestResponder {
+    responders: HashMap<Url, Responder>,
+    revoked: Vec<x509_cert::Certificate>,
+    posts: Arc<AtomicUsize>,
+    responses: Arc<Mutex<Vec<Vec<u8>>>>,
+    // if set, responses are this old and have no next update
+    age: Option<Duration>,
+}
+
+#[async_trait]
+impl Fetcher for TestResponder {
+    type FetcherError = TestResponderError;
+
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>, Self::FetcherError> {
+        Err(TestResponderError(url.clone()))
+    }
+
+    async fn post(
+        &self,
+        url: &Url,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>, Self::FetcherError> {
+        assert_eq!("application/ocsp-request", content_type);
+        self.posts.fetch_add(1, Ordering::SeqCst);
+        let responder = self
+            .responders
+            .get(url)
+            .ok_or_else(|| TestResponderError(url.clone()))?;
+        let revoked = self.revoked.iter().collect::<Vec<_>>();
+        let response = match self.age {
+            None => CertificatePathGenerator::ocsp(
+                &responder.issuer,
+                &responder.key,
+                body.as_slice(),
+                revoked.as_slice(),
+                Duration::from_secs(3600),
+            ),
+            Some(age) => CertificatePathGenerator::ocsp_at(
+                &responder.issuer,
+                &responder.key,
+                body.as_slice(),
+                revoked.as_slice(),
+                SystemTime::now() - age,
+                None,
+            ),
+        }
+        .unwrap();
+        self.responses.lock().unwrap().push(response.clone());
+        Ok(response)
+    }
+}
+
+struct Material {
+    certificates: Vec<x509_cert::Certificate>,
+    keys: Vec<Vec<u8>>,
+    root: Vec<u8>,
+}
+
+impl Material {
+    fn generate() -> Self {
+        let (certificates, keys) = CertificatePathGenerator::generate_with_keys(4, "0").unwrap();
+        let root = certificates.last().unwrap().to_der().unwrap();
+        Self {
+            certificates,
+            keys,
+            root,
+        }
+    }
+
+    fn root(&self) -> &x509_cert::Certificate {
+        self.certificates.last().unwrap()
+    }
+
+    fn path(&self) -> Vec<&x509_cert::Certificate> {
+        self.certificates[..self.certificates.len() - 1]
+            .iter()
+            .collect()
+    }
+
+    fn url(&self, issuer: usize) -> Url {
+        let name = self.certificates[issuer]
+            .tbs_certificate
+            .subject
+            .to_string();
+        Url::parse(format!("test://{}/ocsp", name.trim_start_matches("CN=")).as_str()).unwrap()
+    }
+
+    fn subject(&self, position: usize) -> String {
+        self.certificates[position]
+            .tbs_certificate
+            .subject
+            .to_string()
+    }
+
+    // responder for every issuer in the path, revoking `revoked`
+    fn responder(&self, revoked: &[usize]) -> TestResponder {
+        let mut responders = HashMap::new();
+        for issuer in 1..self.certificates.len() {
+            responders.insert(
+                self.url(issuer),
+                Responder {
+                    issuer: self.certificates[issuer].clone(),
+                    key: self.keys[issuer].clone(),
+                },
+            );
+        }
+        TestResponder {
+            responders,
+            revoked: revoked
+                .iter()
+                .map(|r| self.certificates[*r].clone())
+                .collect(),
+            posts: Arc::new(AtomicUsize::new(0)),
+            responses: Arc::new(Mutex::new(vec![])),
+            age: None,
+        }
+    }
+
+    fn validator(
+        &self,
+        responder: TestResponder,
+    ) -> OcspPathValidator<'_, SyncDefaultPathValidator<'_>, TestResponder> {
+        let root = webpki::TrustAnchor::try_from_cert_der(self.root.as_slice()).unwrap();
+        OcspPathValidator::new(
+            DefaultPathValidator::new_sync(ALGORITHMS, vec![root], KeyUsage::client_auth(), &[]),
+            responder,
+            ALGORITHMS,
+            vec![TrustAnchor::try_from(self.root()).unwrap()],
+            Duration::from_secs(86400),
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_good() {
+    let material = Material::generate();
+    let responder = material.responder(&[]);
+    let posts = responder.posts.clone();
+    let validator = material.validator(responder);
+
+    for _ in 0..2 {
+        assert_eq!(
+            CertificatePathValidation::Found,
+            validator.validate(material.path()).await.unwrap()
+        );
+    }
+
+    // responses cached until next update
+    assert_eq!(3, posts.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_max_age() {
+    let material = Material::generate();
+
+    // without next update, fresh within max age, and not cached
+    let mut responder = material.responder(&[]);
+    responder.age = Some(Duration::from_secs(3600));
+    let posts = responder.posts.clone();
+    let validator = material.validator(responder);
+    for _ in 0..2 {
+        assert_eq!(
+            CertificatePathValidation::Found,
+            validator.validate(material.path()).await.unwrap()
+        );
+    }
+    assert_eq!(6, posts.load(Ordering::SeqCst));
+
+    // older than max age
+    let mut responder = material.responder(&[]);
+    responder.age = Some(Duration::from_secs(2 * 86400));
+    let validator = material.validator(responder);
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.ends_with("response expired"), "{}", reason)
+        }
+        CertificatePathValidation::Found => panic!("response is stale"),
+    }
+}
+
+#[tokio::test]
+async fn test_revoked() {
+    let material = Material::generate();
+
+    // end-entity, and top certificate verified by anchor
+    for revoked in [0, 2] {
+        let validator = material.validator(material.responder(&[revoked]));
+        match validator.validate(material.path()).await.unwrap() {
+            CertificatePathValidation::NotFound(reason) => assert_eq!(
+                format!(
+                    "ocsp certificate revoked: {}: {:?}",
+                    material.subject(revoked),
+                    CrlReason::KeyCompromise
+                ),
+                reason
+            ),
+            CertificatePathValidation::Found => panic!("certificate is revoked"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_unavailable() {
+    let material = Material::generate();
+
+    // responder missing
+    let mut responder = material.responder(&[]);
+    responder.responders.remove(&material.url(1));
+    let validator = material.validator(responder);
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => assert_eq!(
+            OcspFailure::Unavailable {
+                subject: material.subject(0),
+                reasons: vec![format!(
+                    "{}: no responder: {}",
+                    material.url(1),
+                    material.url(1)
+                )],
+            }
+            .to_string(),
+            reason
+        ),
+        CertificatePathValidation::Found => panic!("revocation status is unavailable"),
+    }
+
+    // response signed by wrong key
+    let mut responder = material.responder(&[]);
+    responder.responders.insert(
+        material.url(1),
+        Responder {
+            issuer: material.certificates[1].clone(),
+            key: material.keys[0].clone(),
+        },
+    );
+    let validator = material.validator(responder);
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.ends_with("response signature not verified"))
+        }
+        CertificatePathValidation::Found => panic!("response signature is invalid"),
+    }
+
+    // response signature algorithm not trusted
+    let algorithms: &[&webpki::SignatureAlgorithm] = &[&webpki::ED25519];
+    let root = webpki::TrustAnchor::try_from_cert_der(material.root.as_slice()).unwrap();
+    let validator = OcspPathValidator::new(
+        DefaultPathValidator::new_sync(ALGORITHMS, vec![root], KeyUsage::client_auth(), &[]),
+        material.responder(&[]),
+        algorithms,
+        vec![TrustAnchor::try_from(material.root()).unwrap()],
+        Duration::from_secs(86400),
+    );
+    match validator.validate(material.path()).await.unwrap() {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.contains("response untrusted signature algorithm"))
+        }
+        CertificatePathValidation::Found => panic!("response signature algorithm is untrusted"),
+    }
+}
+
+#[tokio::test]
+async fn test_find() {
+    let material = Material::generate();
+    let certificates = material
+        .path()
+        .into_iter()
+        .map(|c| Arc::new(c.clone()))
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: certificates[1..].to_vec(),
+        ..X509PathFinderConfiguration::new(material.validator(material.responder(&[1])))
+    });
+
+    let report = search.find(certificates[0].clone()).await.unwrap();
+    assert!(report.found.is_none());
+    assert!(report.failures[0]
+        .reason
+        .starts_with(format!("ocsp certificate revoked: {}", material.subject(1)).as_str()));
+}
+
+#[tokio::test]
+async fn test_evidence() {
+    let material = Material::generate();
+    let certificates = material
+        .path()
+        .into_iter()
+        .map(|c| Arc::new(c.clone()))
+        .collect::<Vec<Arc<x509_path_finder::Certificate>>>();
+    let find = |responder| {
+        X509PathFinder::new(X509PathFinderConfiguration {
+            certificates: certificates[1..].to_vec(),
+            ..X509PathFinderConfiguration::new(material.validator(responder))
+        })
+    };
+
+    // fetched responses reported as evidence
+    let report = find(material.responder(&[]))
+        .find(certificates[0].clone())
+        .await
+        .unwrap();
+    let evidence = report.found.unwrap().revocation;
+    assert_eq!(3, evidence.len());
+
+    // supplied responses used, none requested
+    let mut responder = material.responder(&[]);
+    responder.responders.clear();
+    let posts = responder.posts.clone();
+    let report = find(responder)
+        .find_with_evidence(certificates[0].clone(), evidence.clone())
+        .await
+        .unwrap();
+    assert_eq!(evidence, report.found.unwrap().revocation);
+    assert_eq!(0, posts.load(Ordering::SeqCst));
+
+    // stapled response revoking end-entity is used before requesting
+    let responder = material.responder(&[0]);
+    let responses = responder.responses.clone();
+    material
+        .validator(responder)
+        .validate(material.path())
+        .await
+        .unwrap();
+    let stapled = responses.lock().unwrap()[0].clone();
+
+    let report = find(material.responder(&[]))
+        .find_with_evidence(
+            certificates[0].clone(),
+            vec![RevocationEvidence::Ocsp(stapled)],
+        )
+        .await
+        .unwrap();
+    assert!(report.found.is_none());
+    assert!(report.failures[0]
+        .reason
+        .starts_with(format!("ocsp certificate revoked: {}", material.subject(0)).as_str()));
+}
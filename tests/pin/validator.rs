@@ -0,0 +1,162 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use der::Encode;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use webpki::KeyUsage;
+use x509_path_finder::anchor::TrustAnchor;
+use x509_path_finder::api::{CertificatePathValidation, PathValidator};
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder::provided::validator::pin::{PinPathValidator, UNPINNED};
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+fn pin(certificate: &x509_cert::Certificate) -> String {
+    STANDARD.encode(Sha256::digest(
+        certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .unwrap(),
+    ))
+}
+
+fn validator<'a>(
+    roots: &'a [Vec<u8>],
+    pins: &[String],
+    anchors: &[&x509_cert::Certificate],
+) -> PinPathValidator<DefaultPathValidator<'a>> {
+    PinPathValidator::new(
+        DefaultPathValidator::new(
+            ALGORITHMS,
+            roots
+                .iter()
+                .map(|r| webpki::TrustAnchor::try_from_cert_der(r.as_slice()).unwrap())
+                .collect(),
+            KeyUsage::client_auth(),
+            &[],
+        ),
+        pins,
+        anchors
+            .iter()
+            .map(|a| TrustAnchor::try_from(*a).unwrap())
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_pinned() {
+    let certificates = CertificatePathGenerator::generate(4, "0").unwrap();
+    let root = &certificates[3];
+    let roots = vec![root.to_der().unwrap()];
+    let path = certificates[..3]
+        .iter()
+        .collect::<Vec<&x509_cert::Certificate>>();
+
+    // intermediate pinned
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator(&roots, &[pin(&certificates[2])], &[root])
+            .validate(path.clone())
+            .unwrap()
+    );
+
+    // anchor pinned
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator(&roots, &[pin(root)], &[root])
+            .validate(path.clone())
+            .unwrap()
+    );
+
+    // pinned anchor with the root's subject, but another key
+    let impostor = CertificatePathGenerator::generate(4, "0").unwrap();
+    assert_eq!(
+        root.tbs_certificate.subject,
+        impostor[3].tbs_certificate.subject
+    );
+    assert_eq!(
+        CertificatePathValidation::NotFound(UNPINNED.to_string()),
+        validator(&roots, &[pin(&impostor[3])], &[&impostor[3]])
+            .validate(path.clone())
+            .unwrap()
+    );
+
+    // valid, but unpinned
+    let other = CertificatePathGenerator::generate(1, "1").unwrap();
+    assert_eq!(
+        CertificatePathValidation::NotFound(UNPINNED.to_string()),
+        validator(&roots, &[pin(&other[0])], &[root])
+            .validate(path.clone())
+            .unwrap()
+    );
+
+    // invalid paths keep the wrapped validator's reason
+    let other_roots = vec![other[0].to_der().unwrap()];
+    match validator(&other_roots, &[pin(&certificates[2])], &[root])
+        .validate(path)
+        .unwrap()
+    {
+        CertificatePathValidation::NotFound(reason) => assert_ne!(UNPINNED, reason),
+        CertificatePathValidation::Found => panic!("path is invalid"),
+    }
+}
+
+#[test]
+fn test_invalid_pin() {
+    let certificates = CertificatePathGenerator::generate(2, "0").unwrap();
+    let root = certificates[1].to_der().unwrap();
+    for pin in ["not base64!", "AAAA"] {
+        assert!(PinPathValidator::new(
+            DefaultPathValidator::new(
+                ALGORITHMS,
+                vec![webpki::TrustAnchor::try_from_cert_der(root.as_slice()).unwrap()],
+                KeyUsage::client_auth(),
+                &[],
+            ),
+            &[pin],
+            vec![],
+        )
+        .is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_find() {
+    let (certificates, _) = CertificatePathGenerator::generate_with_keys(4, "0").unwrap();
+    let (other, other_keys) = CertificatePathGenerator::generate_with_keys(1, "1").unwrap();
+
+    // top intermediate cross-signed by other root
+    let cross =
+        CertificatePathGenerator::cross(&other[0], &other_keys[0], &certificates[2]).unwrap();
+    let roots = vec![
+        certificates[3].to_der().unwrap(),
+        other[0].to_der().unwrap(),
+    ];
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: vec![
+            Arc::new(certificates[1].clone()),
+            Arc::new(certificates[2].clone()),
+            Arc::new(cross.clone()),
+        ],
+        ..X509PathFinderConfiguration::new(validator(
+            &roots,
+            &[pin(&other[0])],
+            &[&certificates[3], &other[0]],
+        ))
+    });
+
+    let report = search
+        .find(Arc::new(certificates[0].clone()))
+        .await
+        .unwrap();
+    assert_eq!(
+        Arc::new(cross),
+        report.found.unwrap().path.last().unwrap().clone()
+    );
+    assert!(report.failures.iter().all(|f| f.reason == UNPINNED));
+}
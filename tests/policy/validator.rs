@@ -0,0 +1,232 @@
+use der::asn1::OctetString;
+use der::oid::db::rfc5280::{ANY_EXTENDED_KEY_USAGE, ANY_POLICY, ID_CE_CERTIFICATE_POLICIES};
+use der::oid::db::rfc5912::{ID_KP_CLIENT_AUTH, ID_KP_CODE_SIGNING};
+use der::oid::ObjectIdentifier;
+use der::Encode;
+use std::sync::Arc;
+use webpki::KeyUsage;
+use x509_cert::ext::Extension;
+use x509_path_finder::api::{CertificatePathValidation, PathValidator};
+use x509_path_finder::provided::validator::default::DefaultPathValidator;
+use x509_path_finder::provided::validator::policy::{PolicyFailure, PolicyPathValidator};
+use x509_path_finder::{X509PathFinder, X509PathFinderConfiguration};
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+const ID_ENTERPRISE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.55555.1.1");
+const ID_POLICY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.55555.2.1");
+
+struct Material {
+    certificates: Vec<x509_cert::Certificate>,
+    keys: Vec<Vec<u8>>,
+    root: Vec<u8>,
+}
+
+impl Material {
+    fn generate() -> Self {
+        let (certificates, keys) = CertificatePathGenerator::generate_with_keys(3, "0").unwrap();
+        let root = certificates[2].to_der().unwrap();
+        Self {
+            certificates,
+            keys,
+            root,
+        }
+    }
+
+    // end-entity, or intermediate, with extended key `usages`
+    fn usages(&self, i: usize, usages: &[ObjectIdentifier]) -> x509_cert::Certificate {
+        CertificatePathGenerator::with_extended_key_usage(
+            &self.certificates[i],
+            &self.keys[i + 1],
+            usages.to_vec(),
+        )
+        .unwrap()
+    }
+
+    // end-entity, or intermediate, asserting `policies`
+    fn policies(&self, i: usize, policies: &[ObjectIdentifier]) -> x509_cert::Certificate {
+        CertificatePathGenerator::with_policies(
+            &self.certificates[i],
+            &self.keys[i + 1],
+            policies.to_vec(),
+        )
+        .unwrap()
+    }
+
+    fn validator(
+        &self,
+        usages: &[ObjectIdentifier],
+        policies: &[ObjectIdentifier],
+    ) -> PolicyPathValidator<DefaultPathValidator<'_>> {
+        PolicyPathValidator::new(
+            DefaultPathValidator::new(
+                ALGORITHMS,
+                vec![webpki::TrustAnchor::try_from_cert_der(self.root.as_slice()).unwrap()],
+                KeyUsage::client_auth(),
+                &[],
+            ),
+            usages.to_vec(),
+            policies.to_vec(),
+        )
+    }
+}
+
+#[test]
+fn test_extended_key_usage() {
+    let material = Material::generate();
+    let usages = [ID_KP_CODE_SIGNING, ID_ENTERPRISE];
+    let ee = material.usages(0, &[ID_KP_CLIENT_AUTH, ID_KP_CODE_SIGNING, ID_ENTERPRISE]);
+    let validator = material.validator(&usages, &[]);
+
+    // issuer without extended key usage permits every usage
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator
+            .validate(vec![&ee, &material.certificates[1]])
+            .unwrap()
+    );
+
+    // issuer permitting any usage
+    let ic = material.usages(1, &[ID_KP_CLIENT_AUTH, ANY_EXTENDED_KEY_USAGE]);
+    assert_eq!(
+        CertificatePathValidation::Found,
+        validator.validate(vec![&ee, &ic]).unwrap()
+    );
+
+    // issuer constraining usage
+    let ic = material.usages(1, &[ID_KP_CLIENT_AUTH, ID_KP_CODE_SIGNING]);
+    assert_eq!(
+        CertificatePathValidation::NotFound(
+            PolicyFailure::ExtendedKeyUsage {
+                subject: ic.tbs_certificate.subject.to_string(),
+                usage: ID_ENTERPRISE,
+            }
+            .to_string()
+        ),
+        validator.validate(vec![&ee, &ic]).unwrap()
+    );
+
+    // end-entity must list every usage
+    for ee in [
+        material.certificates[0].clone(),
+        material.usages(0, &[ID_KP_CLIENT_AUTH, ID_KP_CODE_SIGNING]),
+    ] {
+        match validator
+            .validate(vec![&ee, &material.certificates[1]])
+            .unwrap()
+        {
+            CertificatePathValidation::NotFound(reason) => {
+                assert!(reason.starts_with("extended key usage not permitted"))
+            }
+            CertificatePathValidation::Found => panic!("usage is not permitted"),
+        }
+    }
+}
+
+#[test]
+fn test_policy() {
+    let material = Material::generate();
+    let ee = material.policies(0, &[ID_POLICY]);
+    let validator = material.validator(&[], &[ID_POLICY]);
+
+    for ic in [
+        material.policies(1, &[ID_POLICY]),
+        material.policies(1, &[ANY_POLICY]),
+    ] {
+        assert_eq!(
+            CertificatePathValidation::Found,
+            validator.validate(vec![&ee, &ic]).unwrap()
+        );
+    }
+
+    // issuer not asserting policy
+    let ic = material.policies(1, &[ID_ENTERPRISE]);
+    assert_eq!(
+        CertificatePathValidation::NotFound(
+            PolicyFailure::Policy {
+                subject: ic.tbs_certificate.subject.to_string(),
+                policy: ID_POLICY,
+            }
+            .to_string()
+        ),
+        validator.validate(vec![&ee, &ic]).unwrap()
+    );
+
+    // end-entity must assert policy itself
+    let ee = material.policies(0, &[ANY_POLICY]);
+    let ic = material.policies(1, &[ANY_POLICY]);
+    assert_eq!(
+        CertificatePathValidation::NotFound(
+            PolicyFailure::Policy {
+                subject: ee.tbs_certificate.subject.to_string(),
+                policy: ID_POLICY,
+            }
+            .to_string()
+        ),
+        validator.validate(vec![&ee, &ic]).unwrap()
+    );
+}
+
+#[test]
+fn test_malformed() {
+    let material = Material::generate();
+    let ee = material.policies(0, &[ID_POLICY]);
+    let ic = CertificatePathGenerator::with_extension(
+        &material.certificates[1],
+        &material.keys[2],
+        Extension {
+            extn_id: ID_CE_CERTIFICATE_POLICIES,
+            critical: false,
+            extn_value: OctetString::new(vec![0x30, 0x03, 0x01]).unwrap(),
+        },
+    )
+    .unwrap();
+
+    // undecodable extension is ignored unless its policies are required
+    assert_eq!(
+        CertificatePathValidation::Found,
+        material
+            .validator(&[], &[])
+            .validate(vec![&ee, &ic])
+            .unwrap()
+    );
+
+    match material
+        .validator(&[], &[ID_POLICY])
+        .validate(vec![&ee, &ic])
+        .unwrap()
+    {
+        CertificatePathValidation::NotFound(reason) => {
+            assert!(reason.starts_with("malformed extension: "))
+        }
+        CertificatePathValidation::Found => panic!("extension is malformed"),
+    }
+}
+
+#[tokio::test]
+async fn test_find() {
+    let material = Material::generate();
+    let ee = material.usages(0, &[ID_KP_CLIENT_AUTH, ID_KP_CODE_SIGNING]);
+    let constrained = material.usages(1, &[ID_KP_CLIENT_AUTH]);
+
+    let mut search = X509PathFinder::new(X509PathFinderConfiguration {
+        certificates: vec![
+            Arc::new(constrained.clone()),
+            Arc::new(material.certificates[1].clone()),
+        ],
+        ..X509PathFinderConfiguration::new(material.validator(&[ID_KP_CODE_SIGNING], &[]))
+    });
+
+    let report = search.find(Arc::new(ee)).await.unwrap();
+    assert_eq!(
+        Arc::new(material.certificates[1].clone()),
+        report.found.unwrap().path[1]
+    );
+    assert!(report.failures.iter().all(|f| f.reason
+        == PolicyFailure::ExtendedKeyUsage {
+            subject: constrained.tbs_certificate.subject.to_string(),
+            usage: ID_KP_CODE_SIGNING,
+        }
+        .to_string()));
+}
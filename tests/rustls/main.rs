@@ -0,0 +1,3 @@
+#![cfg(feature = "rustls")]
+
+mod verifier;
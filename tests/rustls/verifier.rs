@@ -0,0 +1,194 @@
+use der::asn1::Ia5String;
+use der::Encode;
+use rustls::client::ServerCertVerifier;
+use rustls::server::ClientCertVerifier;
+use rustls::{CertificateError, Error, ServerName};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use webpki::KeyUsage;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_path_finder::anchor::TrustAnchor;
+use x509_path_finder::provided::validator::default::{
+    DefaultPathValidator, SyncDefaultPathValidator,
+};
+use x509_path_finder::provided::verifier::rustls::RustlsVerifier;
+use x509_path_finder::X509PathFinderConfiguration;
+use x509_path_finder_material::generate::CertificatePathGenerator;
+
+static ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[&webpki::ECDSA_P256_SHA256];
+
+struct Material {
+    certificates: Vec<x509_cert::Certificate>,
+    root: Vec<u8>,
+}
+
+impl Material {
+    // end-entity presenting www.example.com, intermediate and root
+    fn generate() -> Self {
+        let (mut certificates, keys) =
+            CertificatePathGenerator::generate_with_keys(3, "0").unwrap();
+        certificates[0] = CertificatePathGenerator::with_subject_alt_names(
+            &certificates[0],
+            &keys[1],
+            vec![GeneralName::DnsName(
+                Ia5String::new("www.example.com").unwrap(),
+            )],
+        )
+        .unwrap();
+        let root = certificates[2].to_der().unwrap();
+        Self { certificates, root }
+    }
+
+    fn der(&self, i: usize) -> rustls::Certificate {
+        rustls::Certificate(self.certificates[i].to_der().unwrap())
+    }
+
+    // verifier with `certificates` preloaded
+    fn verifier(
+        &self,
+        usage: KeyUsage,
+        certificates: &[usize],
+    ) -> RustlsVerifier<SyncDefaultPathValidator<'_>> {
+        RustlsVerifier::new(X509PathFinderConfiguration {
+            certificates: certificates
+                .iter()
+                .map(|i| Arc::new(self.certificates[*i].clone()))
+                .collect(),
+            anchors: vec![TrustAnchor::try_from(&self.certificates[2]).unwrap()],
+            ..X509PathFinderConfiguration::new(DefaultPathValidator::new_sync(
+                ALGORITHMS,
+                vec![webpki::TrustAnchor::try_from_cert_der(self.root.as_slice()).unwrap()],
+                usage,
+                &[],
+            ))
+        })
+    }
+}
+
+fn verify_server(
+    verifier: &RustlsVerifier<SyncDefaultPathValidator<'_>>,
+    end_entity: &rustls::Certificate,
+    intermediates: &[rustls::Certificate],
+    server_name: &str,
+) -> Result<(), Error> {
+    verifier
+        .verify_server_cert(
+            end_entity,
+            intermediates,
+            &ServerName::try_from(server_name).unwrap(),
+            &mut std::iter::empty(),
+            &[],
+            SystemTime::now(),
+        )
+        .map(|_| ())
+}
+
+#[test]
+fn test_server() {
+    let material = Material::generate();
+    let ee = material.der(0);
+
+    // complete chain presented
+    let verifier = material.verifier(KeyUsage::server_auth(), &[]);
+    assert!(verify_server(&verifier, &ee, &[material.der(1)], "www.example.com").is_ok());
+
+    // presented intermediates aren't kept for later verifications
+    assert!(verify_server(&verifier, &ee, &[], "www.example.com").is_err());
+
+    // incomplete chain, intermediate preloaded
+    let verifier = material.verifier(KeyUsage::server_auth(), &[1]);
+    assert!(verify_server(&verifier, &ee, &[], "www.example.com").is_ok());
+
+    // wrong name
+    assert_eq!(
+        Err(Error::InvalidCertificate(CertificateError::NotValidForName)),
+        verify_server(&verifier, &ee, &[], "mail.example.com")
+    );
+
+    // incomplete chain, intermediate not found
+    let verifier = material.verifier(KeyUsage::server_auth(), &[]);
+    match verify_server(&verifier, &ee, &[], "www.example.com") {
+        Err(Error::InvalidCertificate(CertificateError::Other(e))) => {
+            assert!(e
+                .to_string()
+                .starts_with("rustls verifier -> path not found"))
+        }
+        result => panic!("path should not be found: {:?}", result),
+    }
+
+    // malformed
+    assert_eq!(
+        Err(Error::InvalidCertificate(CertificateError::BadEncoding)),
+        verify_server(
+            &verifier,
+            &rustls::Certificate(vec![0]),
+            &[],
+            "www.example.com"
+        )
+    );
+}
+
+#[test]
+fn test_client() {
+    let material = Material::generate();
+    let verifier = material.verifier(KeyUsage::client_auth(), &[1]);
+
+    assert_eq!(
+        vec![material.certificates[2]
+            .tbs_certificate
+            .subject
+            .to_der()
+            .unwrap()],
+        verifier
+            .client_auth_root_subjects()
+            .iter()
+            .map(|s| s.as_ref().to_vec())
+            .collect::<Vec<Vec<u8>>>()
+    );
+    assert!(verifier
+        .verify_client_cert(&material.der(0), &[], SystemTime::now())
+        .is_ok());
+
+    let other = CertificatePathGenerator::generate(2, "1").unwrap();
+    assert!(verifier
+        .verify_client_cert(
+            &rustls::Certificate(other[0].to_der().unwrap()),
+            &[],
+            SystemTime::now()
+        )
+        .is_err());
+}
+
+#[test]
+fn test_time() {
+    let material = Material::generate();
+    let verifier = material.verifier(KeyUsage::client_auth(), &[1]);
+    let not_after = material.certificates[0]
+        .tbs_certificate
+        .validity
+        .not_after
+        .to_system_time();
+
+    // validated at the time given by rustls
+    assert!(verifier
+        .verify_client_cert(&material.der(0), &[], SystemTime::now())
+        .is_ok());
+    assert!(verifier
+        .verify_client_cert(
+            &material.der(0),
+            &[],
+            not_after + Duration::from_secs(86400)
+        )
+        .is_err());
+}
+
+// verifiers are called, and dropped, within async runtimes by tls streams
+#[tokio::test]
+async fn test_within_runtime() {
+    let material = Material::generate();
+    let verifier = material.verifier(KeyUsage::server_auth(), &[1]);
+    for _ in 0..2 {
+        assert!(verify_server(&verifier, &material.der(0), &[], "www.example.com").is_ok());
+    }
+    drop(verifier);
+}